@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use aoc_2023::solution::Runner;
+use aoc_2023::utils::{self, Parts};
+
+use crate::cli::BenchArgs;
+use crate::{select_inputs, table};
+
+/// Median times keyed by `(day, input file name, phase)`
+type Baseline = HashMap<(u8, String, String), Duration>;
+
+struct Row {
+    day: u8,
+    file: String,
+    phase: String,
+    /// Sorted times of all repetitions, empty if solving failed
+    times: Vec<Duration>,
+    error: Option<String>,
+}
+
+impl Row {
+    fn min(&self) -> Option<Duration> {
+        self.times.first().copied()
+    }
+
+    fn median(&self) -> Option<Duration> {
+        let n = self.times.len();
+        match n {
+            0 => None,
+            _ if n % 2 == 1 => Some(self.times[n / 2]),
+            _ => Some((self.times[n / 2 - 1] + self.times[n / 2]) / 2),
+        }
+    }
+
+    fn max(&self) -> Option<Duration> {
+        self.times.last().copied()
+    }
+}
+
+/// Parse baseline file
+///
+/// Each non-empty line not starting with `#` contains day, input file name,
+/// phase and median time in nanoseconds separated by whitespace.
+fn parse_baseline(input: &str) -> Result<Baseline, Box<dyn std::error::Error>> {
+    let mut baseline = HashMap::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let invalid = || format!("Invalid baseline line {}: {}", i + 1, line);
+        let [day, file, phase, nanos] = fields[..] else {
+            return Err(invalid().into());
+        };
+        let day = day.parse::<u8>().map_err(|_| invalid())?;
+        let nanos = nanos.parse::<u64>().map_err(|_| invalid())?;
+
+        baseline.insert(
+            (day, file.to_owned(), phase.to_owned()),
+            Duration::from_nanos(nanos),
+        );
+    }
+
+    Ok(baseline)
+}
+
+fn format_baseline(rows: &[Row]) -> String {
+    let mut output = String::from("# day input phase median_ns\n");
+    for row in rows {
+        if let Some(median) = row.median() {
+            let _ = writeln!(
+                output,
+                "{} {} {} {}",
+                row.day,
+                row.file,
+                row.phase,
+                median.as_nanos()
+            );
+        }
+    }
+    output
+}
+
+/// Solve `file` `repeat` times, returning a row for parsing and every selected part
+fn bench_file(runner: &dyn Runner, file: &str, parts: Parts, repeat: u32) -> Vec<Row> {
+    let day = runner.day();
+    let label = utils::input_label(file);
+    let name = Path::new(label)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(label)
+        .to_owned();
+
+    let failed = |phase: &str, error: String| Row {
+        day,
+        file: name.clone(),
+        phase: phase.to_owned(),
+        times: Vec::new(),
+        error: Some(error),
+    };
+
+    let input = match utils::read_input(file) {
+        Ok(input) => input,
+        Err(e) => return vec![failed("Parse", e.to_string())],
+    };
+
+    let mut rows: Vec<Row> = Vec::new();
+
+    for _ in 0..repeat {
+        let result = match runner.run(&input, parts) {
+            Ok(result) => result,
+            Err(e) => return vec![failed("Parse", e.with_file(label).to_string())],
+        };
+
+        let times = std::iter::once(("Parse".to_owned(), result.parse_time, None)).chain(
+            result.parts.into_iter().map(|part| {
+                let error = part.answer.err().map(|e| e.with_file(label).to_string());
+                (part.part.to_string(), part.time, error)
+            }),
+        );
+
+        for (phase, time, error) in times {
+            let index = match rows.iter().position(|row| row.phase == phase) {
+                Some(index) => index,
+                None => {
+                    rows.push(Row {
+                        day,
+                        file: name.clone(),
+                        phase,
+                        times: Vec::new(),
+                        error: None,
+                    });
+                    rows.len() - 1
+                }
+            };
+
+            let row = &mut rows[index];
+            if error.is_some() {
+                row.error = error;
+            }
+            row.times.push(time);
+        }
+    }
+
+    for row in &mut rows {
+        if row.error.is_some() {
+            // Time of a failed solve says nothing about the solution
+            row.times.clear();
+        }
+        row.times.sort();
+    }
+
+    rows
+}
+
+fn print_rows(rows: &[Row], baseline: Option<&Baseline>) {
+    let format =
+        |time: Option<Duration>| time.map(|time| format!("{:.2?}", time)).unwrap_or_default();
+
+    let header = [
+        "DAY", "INPUT", "PHASE", "MIN", "MEDIAN", "MAX", "BASELINE", "CHANGE",
+    ];
+    let cells = rows
+        .iter()
+        .map(|row| {
+            let previous = baseline
+                .and_then(|baseline| baseline.get(&(row.day, row.file.clone(), row.phase.clone())));
+            let change = match (previous, row.median()) {
+                (Some(previous), Some(median)) if !previous.is_zero() => format!(
+                    "{:+.1}%",
+                    (median.as_secs_f64() / previous.as_secs_f64() - 1.0) * 100.0
+                ),
+                _ => String::new(),
+            };
+
+            match &row.error {
+                Some(e) => [
+                    format!("{:02}", row.day),
+                    row.file.clone(),
+                    row.phase.clone(),
+                    format!("Error: {}", e),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ],
+                None => [
+                    format!("{:02}", row.day),
+                    row.file.clone(),
+                    row.phase.clone(),
+                    format(row.min()),
+                    format(row.median()),
+                    format(row.max()),
+                    format(previous.copied()),
+                    change,
+                ],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    table::print_table(header, &cells);
+}
+
+/// Solve all inputs of selected days repeatedly and report min/median/max
+/// time of parsing and each part
+///
+/// Returns `false` if solving any input fails.
+pub fn bench(
+    registry: &[Box<dyn Runner>],
+    input_dir: &Path,
+    args: &BenchArgs,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let baseline = match &args.baseline {
+        Some(file) => Some(parse_baseline(&std::fs::read_to_string(file)?)?),
+        None => None,
+    };
+    let parts = args.select.parts();
+
+    let mut rows = Vec::new();
+
+    for (runner, files) in select_inputs(registry, input_dir, &args.select)? {
+        for file in files {
+            rows.extend(bench_file(runner, &file, parts, args.repeat));
+        }
+    }
+
+    print_rows(&rows, baseline.as_ref());
+
+    if let Some(file) = &args.save_baseline {
+        std::fs::write(file, format_baseline(&rows))?;
+        println!();
+        println!("Baseline saved to {}", file);
+    }
+
+    Ok(rows.iter().all(|row| row.error.is_none()))
+}
@@ -0,0 +1,290 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use aoc_2023::day08::NodeSet;
+use aoc_2023::day10::AreaMethod;
+use aoc_2023::utils::{Parts, DEFAULT_INPUT_DIR, INPUT_DIR_ENV};
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Advent of Code 2023 solutions")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Directory searched for `dayNN*` inputs
+    #[arg(long, global = true, env = INPUT_DIR_ENV, default_value = DEFAULT_INPUT_DIR)]
+    pub input_dir: PathBuf,
+
+    /// Config file with the session token and server settings
+    #[arg(long, global = true, default_value = "aoc.toml")]
+    pub config: PathBuf,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Solve selected days and parts (default command)
+    Run(RunArgs),
+    /// Check answers of all inputs against the expected answers file
+    Verify(VerifyArgs),
+    /// Repeatedly solve selected days and report how long each phase takes
+    Bench(BenchArgs),
+    /// Download puzzle inputs of selected days into the input directory
+    Fetch(FetchArgs),
+    /// Submit answer of a single part and record the response
+    Submit(SubmitArgs),
+    /// Find seeds leading to the lowest or a given location
+    Day05(Day05Args),
+    /// Follow ghosts between custom start and end nodes, export the network
+    Day08(Day08Args),
+    /// Count tiles enclosed by the pipe loop and cross-check both counting methods
+    Day10(Day10Args),
+    /// List arrangements of damaged springs in day 12 rows
+    Day12(Day12Args),
+    /// Solve a nonogram using day 12 row logic and print the picture
+    Nonogram(NonogramArgs),
+}
+
+#[derive(Args, Debug, Default)]
+pub struct DayArgs {
+    /// Days to run, e.g. `12`, `1-5` or `1,3,10-12` (default: all days)
+    #[arg(short, long, value_parser = parse_days)]
+    pub day: Vec<DaySet>,
+}
+
+impl DayArgs {
+    /// Selected days in ascending order, `None` means all days
+    pub fn days(&self) -> Option<Vec<u8>> {
+        if self.day.is_empty() {
+            return None;
+        }
+
+        let mut days: Vec<u8> = self
+            .day
+            .iter()
+            .flat_map(|set| set.0.iter().copied())
+            .collect();
+        days.sort();
+        days.dedup();
+        Some(days)
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct SelectArgs {
+    #[command(flatten)]
+    pub days: DayArgs,
+
+    /// Part to run (default: both parts)
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Input file or glob pattern to use instead of `dayNN*` files from
+    /// the input directory; `-` reads stdin
+    #[arg(short, long)]
+    pub input: Vec<String>,
+}
+
+impl SelectArgs {
+    pub fn parts(&self) -> Parts {
+        match self.part {
+            Some(1) => Parts::ONLY_PART1,
+            Some(2) => Parts::ONLY_PART2,
+            _ => Parts::BOTH,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human readable lines
+    #[default]
+    Text,
+    /// Array of records, one per day, part and input
+    Json,
+    /// Header and a row per day, part and input
+    Csv,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub select: SelectArgs,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t)]
+    pub format: Format,
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    #[command(flatten)]
+    pub days: DayArgs,
+
+    /// File with expected answers (default: `answers.txt` in the input directory)
+    #[arg(short, long)]
+    pub answers: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub select: SelectArgs,
+
+    /// Number of times each input is solved
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub repeat: u32,
+
+    /// Save median times to this file, to be compared against later
+    #[arg(long)]
+    pub save_baseline: Option<String>,
+
+    /// Compare median times against a previously saved baseline
+    #[arg(long)]
+    pub baseline: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct FetchArgs {
+    #[command(flatten)]
+    pub days: DayArgs,
+
+    /// Download inputs even if they are already cached
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SubmitArgs {
+    /// Day of the puzzle
+    #[arg(short, long, value_parser = parse_day)]
+    pub day: u8,
+
+    /// Part of the puzzle
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: u8,
+
+    /// Answer to submit (default: solve the downloaded `dayNN.txt` input)
+    pub answer: Option<String>,
+
+    /// Input to solve instead of the downloaded one; `-` reads stdin
+    #[arg(short, long, conflicts_with = "answer")]
+    pub input: Option<String>,
+
+    /// Submit even if the answer is outside bounds reported by earlier attempts
+    #[arg(long)]
+    pub force: bool,
+
+    /// File recording all submitted answers (default: `history.txt` in the input directory)
+    #[arg(long)]
+    pub history: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Day05Args {
+    /// Input to inspect; `-` reads stdin (default: the downloaded `day05.txt`)
+    #[arg(short, long)]
+    pub input: Option<String>,
+
+    /// Treat seed values as `start length` pairs like part 2 does
+    #[arg(long)]
+    pub ranges: bool,
+
+    /// Location to trace back instead of the lowest one
+    #[arg(long)]
+    pub location: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct Day08Args {
+    /// Input to inspect; `-` reads stdin (default: the downloaded `day08.txt`)
+    #[arg(short, long)]
+    pub input: Option<String>,
+
+    /// Start nodes, comma separated names or glob patterns (part 2 uses `*A`)
+    #[arg(long, default_value = "AAA")]
+    pub start: NodeSet,
+
+    /// End nodes, comma separated names or glob patterns (part 2 uses `*Z`)
+    #[arg(long, default_value = "ZZZ")]
+    pub end: NodeSet,
+
+    /// Write the network as a Graphviz DOT graph to this file; `-` prints it
+    #[arg(long)]
+    pub dot: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct Day10Args {
+    /// Input to inspect; `-` reads stdin (default: the downloaded `day10.txt`)
+    #[arg(short, long)]
+    pub input: Option<String>,
+
+    /// Counting method, `flood` or `shoelace`; part 2 uses flood fill and falls back to
+    /// the shoelace formula when it can't tell inside from outside
+    #[arg(long, default_value = "flood")]
+    pub area: AreaMethod,
+}
+
+#[derive(Args, Debug)]
+pub struct Day12Args {
+    /// Input to inspect; `-` reads stdin (default: the downloaded `day12.txt`)
+    #[arg(short, long)]
+    pub input: Option<String>,
+
+    /// Row to inspect, starting from 1 (default: all rows)
+    #[arg(long)]
+    pub row: Option<usize>,
+
+    /// Number of copies rows are unfolded to (part 2 uses 5), several comma separated
+    /// factors report total counts of all of them instead of listing arrangements
+    #[arg(long, value_delimiter = ',', default_value = "1")]
+    pub unfold: Vec<usize>,
+
+    /// Springs put between copies of unfolded rows
+    #[arg(long, default_value = "?")]
+    pub joiner: String,
+
+    /// Maximal number of arrangements listed per row
+    #[arg(short, long, default_value_t = 10)]
+    pub limit: usize,
+
+    /// Print only the arrangement with this index in lexicographic order, starting from 0
+    #[arg(long, conflicts_with = "limit")]
+    pub nth: Option<u128>,
+}
+
+#[derive(Args, Debug)]
+pub struct NonogramArgs {
+    /// File with clues of rows, an empty line and clues of columns; `-` reads stdin
+    pub input: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct DaySet(Vec<u8>);
+
+fn parse_day(day: &str) -> Result<u8, String> {
+    match day.trim().parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("Invalid day: {}", day)),
+    }
+}
+
+fn parse_days(days: &str) -> Result<DaySet, String> {
+    let mut result = Vec::new();
+
+    for item in days.split(',') {
+        if let Some((from, to)) = item.split_once('-') {
+            let from = parse_day(from)?;
+            let to = parse_day(to)?;
+            if from > to {
+                return Err(format!("Invalid day range: {}", item));
+            }
+            result.extend(from..=to);
+        } else {
+            result.push(parse_day(item)?);
+        }
+    }
+
+    Ok(DaySet(result))
+}
@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+
+/// HTTP client for the Advent of Code website
+///
+/// Requests are spaced at least `min_interval` seconds apart, even across
+/// separate runs - time of the last request is kept in `state_file`.
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    year: u16,
+    session: String,
+    min_interval: Duration,
+    state_file: PathBuf,
+}
+
+impl Client {
+    pub fn new(config: &Config, state_file: PathBuf) -> Result<Client, String> {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(&config.user_agent)
+            .timeout(Duration::from_secs(30))
+            .build();
+
+        Ok(Client {
+            agent,
+            base_url: config.base_url.trim_end_matches('/').to_owned(),
+            year: config.year,
+            session: config.session()?.to_owned(),
+            min_interval: Duration::from_secs(config.min_interval),
+            state_file,
+        })
+    }
+
+    fn url(&self, day: u8, path: &str) -> String {
+        format!("{}/{}/day/{}{}", self.base_url, self.year, day, path)
+    }
+
+    fn last_request(&self) -> Option<SystemTime> {
+        let millis = std::fs::read_to_string(&self.state_file).ok()?;
+        let millis = millis.trim().parse::<u64>().ok()?;
+        Some(UNIX_EPOCH + Duration::from_millis(millis))
+    }
+
+    /// Wait until enough time passed since the last request and record a new one
+    fn throttle(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(last) = self.last_request() {
+            let elapsed = last.elapsed().unwrap_or_default();
+            if elapsed < self.min_interval {
+                let wait = self.min_interval - elapsed;
+                println!("Waiting {:.1?} before the next request", wait);
+                std::thread::sleep(wait);
+            }
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        std::fs::write(&self.state_file, now.as_millis().to_string())?;
+        Ok(())
+    }
+
+    fn send(
+        &self,
+        request: ureq::Request,
+        form: Option<&[(&str, &str)]>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        self.throttle()?;
+
+        let request = request.set("Cookie", &format!("session={}", self.session));
+        let response = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(429, response)) => {
+                let retry = response.header("Retry-After").unwrap_or("later").to_owned();
+                Err(format!("Rate limited by the server, retry after {}", retry).into())
+            }
+            Err(ureq::Error::Status(404, _)) => {
+                Err("Puzzle not found, is the day unlocked yet?".into())
+            }
+            Err(ureq::Error::Status(400 | 401 | 403, _)) => {
+                Err("Request rejected, is the session token valid?".into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Download puzzle input of `day`
+    pub fn input(&self, day: u8) -> Result<String, Box<dyn std::error::Error>> {
+        self.send(self.agent.get(&self.url(day, "/input")), None)
+    }
+
+    /// Post `answer` of `part` (1 or 2) of `day`, returning the response page
+    pub fn answer(
+        &self,
+        day: u8,
+        part: u8,
+        answer: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let level = part.to_string();
+        let form = [("level", level.as_str()), ("answer", answer)];
+        self.send(self.agent.post(&self.url(day, "/answer")), Some(&form))
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// Environment variable overriding the session token from the config file
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// Environment variable overriding the base URL from the config file
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+
+/// Settings for talking to the Advent of Code website (or a compatible server)
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Value of the `session` cookie of a logged-in user
+    pub session: Option<String>,
+    pub base_url: String,
+    pub year: u16,
+    pub user_agent: String,
+    /// Minimal number of seconds between two requests
+    pub min_interval: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            session: None,
+            base_url: "https://adventofcode.com".to_owned(),
+            year: 2023,
+            user_agent: "github.com/Anty0/AoC-23".to_owned(),
+            min_interval: 5,
+        }
+    }
+}
+
+impl Config {
+    /// Load config from `file` (defaults if it doesn't exist) and apply environment overrides
+    pub fn load(file: &Path) -> Result<Config, Box<dyn std::error::Error>> {
+        let mut config = if file.exists() {
+            toml::from_str(&std::fs::read_to_string(file)?)
+                .map_err(|e| format!("Invalid config {}: {}", file.display(), e))?
+        } else {
+            Config::default()
+        };
+
+        if let Ok(session) = std::env::var(SESSION_ENV) {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = std::env::var(BASE_URL_ENV) {
+            config.base_url = base_url;
+        }
+
+        Ok(config)
+    }
+
+    pub fn session(&self) -> Result<&str, String> {
+        match self.session.as_deref() {
+            Some(session) if !session.trim().is_empty() => Ok(session.trim()),
+            _ => Err(format!(
+                "Missing session token, set `session` in the config file or {}",
+                SESSION_ENV
+            )),
+        }
+    }
+}
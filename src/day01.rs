@@ -1,7 +1,8 @@
 use const_format::concatcp;
 use regex::Regex;
 
-use crate::utils;
+use crate::error::{Error, ParseError};
+use crate::solution::Solution;
 
 const NUM_PART1_RE: &str = "([0-9])";
 const NUM_PART2_RE: &str = "([0-9]|one|two|three|four|five|six|seven|eight|nine)";
@@ -11,29 +12,39 @@ const LINE_PART1_RE_STR: &str =
 const LINE_PART2_RE_STR: &str =
     concatcp!("(?m)^(?U:.*)", NUM_PART2_RE, r".*", NUM_PART2_RE, r".*$");
 
-pub fn day01() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day01")? {
-        handle(run(&file, false));
-        handle(run(&file, true));
+/// Day 1: Trebuchet?!
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<Calibration>;
+    type Answer = u32;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(input.as_bytes())
     }
-    Ok(())
-}
 
-fn handle(result: Result<u32, Box<dyn std::error::Error>>) {
-    match result {
-        Ok(sum) => println!("Result: {}", sum),
-        Err(e) => println!("Error: {}", e),
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        input
+            .iter()
+            .map(|calibration| calibration.digits.clone())
+            .sum::<Result<u32, _>>()
+            .map_err(Error::from)
     }
-    println!();
-}
 
-fn run(file: &str, part2: bool) -> Result<u32, Box<dyn std::error::Error>> {
-    println!("DAY01: Part{}: {}", if part2 { "2" } else { "1" }, file);
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        Ok(input.iter().map(|calibration| calibration.spelled).sum())
+    }
+}
 
-    let file = std::fs::File::open(file)?;
-    let reader = std::io::BufReader::new(file);
-    let result = sum_lines(reader, part2)?;
-    Ok(result)
+/// Calibration value of a line read by both parts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Calibration {
+    /// Value from digits only, lines with just spelled out digits fail part 1
+    pub digits: Result<u32, ParseError>,
+    /// Value from digits and spelled out digits
+    pub spelled: u32,
 }
 
 fn parse_num(c: &str) -> u32 {
@@ -51,57 +62,55 @@ fn parse_num(c: &str) -> u32 {
     }
 }
 
-fn sum_lines<R: std::io::BufRead>(
-    reader: R,
-    part2: bool,
-) -> Result<u32, Box<dyn std::error::Error>> {
-    let mut sum = 0;
-
-    let num_re;
-    let line_re;
-    if part2 {
-        num_re = Regex::new(NUM_PART2_RE)?;
-        line_re = Regex::new(LINE_PART2_RE_STR)?;
-    } else {
-        num_re = Regex::new(NUM_PART1_RE)?;
-        line_re = Regex::new(LINE_PART1_RE_STR)?;
+/// Parse calibration values of all lines, lines without any digit are invalid
+pub fn parse_input<R: std::io::BufRead>(reader: R) -> Result<Vec<Calibration>, Error> {
+    let part1_res = (Regex::new(NUM_PART1_RE)?, Regex::new(LINE_PART1_RE_STR)?);
+    let part2_res = (Regex::new(NUM_PART2_RE)?, Regex::new(LINE_PART2_RE_STR)?);
+
+    let mut calibrations = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let l = line?;
+        calibrations.push(Calibration {
+            digits: calibration_value(&part1_res, i + 1, &l),
+            spelled: calibration_value(&part2_res, i + 1, &l)?,
+        });
     }
 
-    for line in reader.lines() {
-        let l = line?.to_owned();
-        let num1;
-        let num2;
-
-        let captures = line_re.captures(l.as_str());
-        match captures {
-            Some(captures) => {
-                // Multiple numbers present
-                num1 = parse_num(captures.get(1).unwrap().as_str());
-                num2 = parse_num(captures.get(2).unwrap().as_str());
-            }
-            None => {
-                // Only one number present
-                let captures_num = num_re.captures(l.as_str());
-                match captures_num {
-                    Some(captures_num) => {
-                        num1 = parse_num(captures_num.get(1).unwrap().as_str());
-                        num2 = num1
-                    }
-                    None => {
-                        // No numbers present - invalid line
-                        return Err(std::io::Error::new(
-                            std::io::ErrorKind::InvalidData,
-                            "No match",
-                        )
-                        .into());
-                    }
+    Ok(calibrations)
+}
+
+/// First and last digit of the line matched by `(num_re, line_re)`
+fn calibration_value(
+    (num_re, line_re): &(Regex, Regex),
+    line: usize,
+    l: &str,
+) -> Result<u32, ParseError> {
+    let num1;
+    let num2;
+
+    let captures = line_re.captures(l);
+    match captures {
+        Some(captures) => {
+            // Multiple numbers present
+            num1 = parse_num(captures.get(1).unwrap().as_str());
+            num2 = parse_num(captures.get(2).unwrap().as_str());
+        }
+        None => {
+            // Only one number present
+            let captures_num = num_re.captures(l);
+            match captures_num {
+                Some(captures_num) => {
+                    num1 = parse_num(captures_num.get(1).unwrap().as_str());
+                    num2 = num1
+                }
+                None => {
+                    // No numbers present - invalid line
+                    return Err(ParseError::new(line, l, "No digit found"));
                 }
             }
         }
-
-        // println!("{} {} {}", n1, n2, l);
-        sum += num1 * 10 + num2;
     }
 
-    Ok(sum)
+    // println!("{} {} {}", n1, n2, l);
+    Ok(num1 * 10 + num2)
 }
@@ -2,48 +2,67 @@ use const_format::concatcp;
 use regex::Regex;
 use std::cmp;
 
-use crate::utils;
+use crate::error::{parse_number, Error, ParseError};
+use crate::solution::Solution;
 
 const COLOR_RE: &str = r"(?P<amount>[0-9]+) (?P<color>red|green|blue)";
 // const ROUND_RE: &str = concatcp!(" *(", COLOR_RE, r"[,;]?)* *");
 const LINE_RE: &str = concatcp!("^Game (?<game_id>[0-9]+): (?<games>.*)$");
 
-pub fn day02() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day02")? {
-        handle(run(&file));
-    }
-    Ok(())
-}
+/// Day 2: Cube Conundrum
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
 
-fn handle(result: Result<(u32, u32), Box<dyn std::error::Error>>) {
-    match result {
-        Ok((sum_p1, sum_p2)) => println!("Result: Part1={} Part2={}", sum_p1, sum_p2),
-        Err(e) => println!("Error: {}", e),
+    type Input = Vec<Game>;
+    type Answer = u32;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(input.as_bytes())
     }
-    println!();
-}
 
-fn run(file: &str) -> Result<(u32, u32), Box<dyn std::error::Error>> {
-    println!("DAY02: {}", file);
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        const RED: u32 = 12;
+        const GREEN: u32 = 13;
+        const BLUE: u32 = 14;
 
-    let file = std::fs::File::open(file)?;
-    let reader = std::io::BufReader::new(file);
-    let result = sum_lines(reader)?;
-    Ok(result)
+        Ok(input
+            .iter()
+            .filter(|game| game.red <= RED && game.green <= GREEN && game.blue <= BLUE)
+            .map(|game| game.id)
+            .sum())
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        Ok(input.iter().map(Game::power).sum())
+    }
 }
 
-fn sum_lines<R: std::io::BufRead>(reader: R) -> Result<(u32, u32), Box<dyn std::error::Error>> {
-    const RED: u32 = 12;
-    const GREEN: u32 = 13;
-    const BLUE: u32 = 14;
+/// Game and the most cubes of each color shown at once, which is also the fewest cubes needed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+}
 
-    let mut sum_p1 = 0;
-    let mut sum_p2 = 0;
+impl Game {
+    /// Power of the minimal cube set
+    pub fn power(&self) -> u32 {
+        self.red * self.green * self.blue
+    }
+}
 
+/// Parse games
+pub fn parse_input<R: std::io::BufRead>(reader: R) -> Result<Vec<Game>, Error> {
     let line_re = Regex::new(LINE_RE)?;
     let color_re = Regex::new(COLOR_RE)?;
 
-    for line in reader.lines() {
+    let mut result = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
         let l = line?.to_owned();
 
         let captures = line_re.captures(l.as_str());
@@ -55,51 +74,29 @@ fn sum_lines<R: std::io::BufRead>(reader: R) -> Result<(u32, u32), Box<dyn std::
             let mut min_red = 0;
             let mut min_green = 0;
             let mut min_blue = 0;
-            let mut invalid = false;
             for (_, [amount, color]) in color_re.captures_iter(games).map(|c| c.extract()) {
-                let amount = amount.parse::<u32>()?;
-                let limit = match color {
-                    "red" => RED,
-                    "green" => GREEN,
-                    "blue" => BLUE,
-                    _ => {
-                        return Err(std::io::Error::new(
-                            std::io::ErrorKind::InvalidData,
-                            "Invalid color",
-                        )
-                        .into())
-                    }
-                };
-
-                if amount > limit {
-                    invalid = true;
-                }
+                let amount = parse_number::<u32>(i + 1, &l, amount)?;
 
                 match color {
                     "red" => min_red = cmp::max(min_red, amount),
                     "green" => min_green = cmp::max(min_green, amount),
                     "blue" => min_blue = cmp::max(min_blue, amount),
-                    _ => {
-                        return Err(std::io::Error::new(
-                            std::io::ErrorKind::InvalidData,
-                            "Invalid color",
-                        )
-                        .into())
-                    }
+                    _ => return Err(ParseError::within(i + 1, &l, color, "Invalid color").into()),
                 }
 
                 // println!("{}: {}", amount, color);
             }
 
-            if !invalid {
-                sum_p1 += game_id.parse::<u32>()?;
-            }
-
-            sum_p2 += min_red * min_green * min_blue;
+            result.push(Game {
+                id: parse_number::<u32>(i + 1, &l, game_id)?,
+                red: min_red,
+                green: min_green,
+                blue: min_blue,
+            });
         } else {
-            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "No match").into());
+            return Err(Error::parse(i + 1, &l, "Invalid game"));
         }
     }
 
-    Ok((sum_p1, sum_p2))
+    Ok(result)
 }
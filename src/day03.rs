@@ -1,163 +1,131 @@
 use itertools::Itertools;
-use std::collections::HashMap;
 
-use crate::utils;
+use crate::error::{Error, ParseError};
+use crate::solution::Solution;
+use crate::utils::grid::{Grid, Pos};
 
-pub fn day03() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day03")? {
-        handle(run(&file));
-    }
-    Ok(())
-}
+/// Day 3: Gear Ratios
+pub struct Day03;
 
-fn handle(result: Result<(u32, u32), Box<dyn std::error::Error>>) {
-    match result {
-        Ok((sum_p1, sum_p2)) => println!("Result: Part1={} Part2={}", sum_p1, sum_p2),
-        Err(e) => println!("Error: {}", e),
-    }
-    println!();
-}
+impl Solution for Day03 {
+    const DAY: u8 = 3;
 
-fn run(file: &str) -> Result<(u32, u32), Box<dyn std::error::Error>> {
-    println!("DAY03: {}", file);
+    type Input = Schematic;
+    type Answer = u32;
 
-    let file = std::fs::File::open(file)?;
-    let reader = std::io::BufReader::new(file);
-    let schematic = parse_schematic(reader)?;
-    // println!("{:?}", schematic);
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_schematic(input)
+    }
 
-    let sum_p1 = schematic.active_values().sum::<u32>();
-    let sum_p2 = schematic
-        .gear_values()
-        .map(|values| values.iter().product::<u32>())
-        .sum::<u32>();
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        Ok(input.active_values().sum::<u32>())
+    }
 
-    Ok((sum_p1, sum_p2))
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        Ok(input
+            .gear_values()
+            .map(|values| values.iter().product::<u32>())
+            .sum::<u32>())
+    }
 }
 
+/// Part numbers and symbols of an engine schematic
 #[derive(Debug)]
-struct Schematic {
-    occupied_to_id: HashMap<(usize, usize), u32>,
-    id_to_value: HashMap<u32, u32>,
-    gear_occupied: Vec<Vec<(usize, usize)>>,
-    active: Vec<(usize, usize)>,
+pub struct Schematic {
+    /// Id of the number occupying each cell
+    ids: Grid<Option<usize>>,
+    id_to_value: Vec<u32>,
+    symbols: Vec<(Pos, char)>,
 }
 
 impl Schematic {
-    fn new() -> Self {
-        Self {
-            occupied_to_id: HashMap::new(),
-            id_to_value: HashMap::new(),
-            gear_occupied: Vec::new(),
-            active: Vec::new(),
-        }
+    /// Unique ids of numbers adjacent to `pos`
+    fn adjacent_ids(&self, pos: Pos) -> Vec<usize> {
+        self.ids
+            .neighbours8(pos)
+            .filter_map(|p| self.ids[p])
+            .unique()
+            .collect()
     }
 
-    fn active_ids(&self) -> impl Iterator<Item = u32> + '_ {
-        self.active
+    fn active_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.symbols
             .iter()
-            .filter_map(move |p| self.occupied_to_id.get(p))
-            .copied()
+            .flat_map(move |&(pos, _)| self.adjacent_ids(pos))
             .unique()
     }
 
-    fn active_values(&self) -> impl Iterator<Item = u32> + '_ {
-        self.active_ids().map(move |id| self.id_to_value[&id])
+    /// Values of numbers adjacent to a symbol
+    pub fn active_values(&self) -> impl Iterator<Item = u32> + '_ {
+        self.active_ids().map(move |id| self.id_to_value[id])
     }
 
-    fn gear_ids(&self) -> impl Iterator<Item = Vec<u32>> + '_ {
-        self.gear_occupied.iter().filter_map(move |occupied| {
-            let ids: Vec<u32> = occupied
-                .iter()
-                .filter_map(move |p| self.occupied_to_id.get(p))
-                .copied()
-                .unique()
-                .collect();
-            match ids.len() {
-                2 => Some(ids),
-                _ => None,
-            }
-        })
+    fn gear_ids(&self) -> impl Iterator<Item = Vec<usize>> + '_ {
+        self.symbols
+            .iter()
+            .filter(|&&(_, symbol)| symbol == '*')
+            .filter_map(move |&(pos, _)| {
+                let ids = self.adjacent_ids(pos);
+                match ids.len() {
+                    2 => Some(ids),
+                    _ => None,
+                }
+            })
     }
 
-    fn gear_values(&self) -> impl Iterator<Item = Vec<u32>> + '_ {
+    /// Values of number pairs adjacent to the same `*` symbol
+    pub fn gear_values(&self) -> impl Iterator<Item = Vec<u32>> + '_ {
         self.gear_ids().map(move |ids| {
             ids.iter()
-                .map(move |id| self.id_to_value[id])
+                .map(move |&id| self.id_to_value[id])
                 .collect::<Vec<u32>>()
         })
     }
 }
 
-fn parse_schematic<R: std::io::BufRead>(
-    reader: R,
-) -> Result<Schematic, Box<dyn std::error::Error>> {
-    let mut next_id = 0;
-    let mut number_buffer = String::new();
-    let mut schematic = Schematic::new();
-
-    macro_rules! end_number {
-        ($x:expr, $y:expr) => {
-            if !number_buffer.is_empty() {
-                let id = next_id;
-                next_id += 1;
-
-                let value = number_buffer.parse::<u32>()?;
-                for x in $x - number_buffer.len()..$x {
-                    schematic.occupied_to_id.insert((x, $y), id);
-                    schematic.id_to_value.insert(id, value);
-                }
-                schematic.id_to_value.insert(id, value);
-                number_buffer.clear();
-            }
-        };
-    }
-
-    macro_rules! add_active {
-        ($x:expr, $y:expr) => {
-            for y in $y - 1..=$y + 1 {
-                for x in $x - 1..=$x + 1 {
-                    schematic.active.push((x, y));
+/// Parse engine schematic
+pub fn parse_schematic(input: &str) -> Result<Schematic, Error> {
+    let grid = Grid::parse(input, Some)?;
+
+    let mut ids = Grid::filled(grid.width(), grid.height(), None);
+    let mut id_to_value = Vec::new();
+    let mut symbols = Vec::new();
+
+    for (y, row) in grid.rows().enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            let c = row[x];
+            if !c.is_ascii_digit() {
+                if c != '.' {
+                    symbols.push(((x, y), c));
                 }
+                x += 1;
+                continue;
             }
-        };
-    }
 
-    macro_rules! add_gear {
-        ($x:expr, $y:expr) => {
-            let mut occupied = Vec::new();
-
-            for y in $y - 1..=$y + 1 {
-                for x in $x - 1..=$x + 1 {
-                    occupied.push((x, y));
-                }
+            let start = x;
+            while x < row.len() && row[x].is_ascii_digit() {
+                x += 1;
             }
 
-            schematic.gear_occupied.push(occupied);
-        };
-    }
-
-    for (y, line) in reader.lines().enumerate() {
-        let line = line?;
-        let mut max_x = 0;
-        for (x, c) in line.chars().enumerate() {
-            max_x = x;
-            match c {
-                '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => number_buffer.push(c),
-                '.' => end_number!(x, y),
-                '*' => {
-                    end_number!(x, y);
-                    add_active!(x, y);
-                    add_gear!(x, y);
-                }
-                _ => {
-                    end_number!(x, y);
-                    add_active!(x, y);
-                }
+            let number = row[start..x].iter().collect::<String>();
+            let value = number.parse::<u32>().map_err(|e| {
+                let mut error = ParseError::new(y + 1, &number, format!("Invalid number: {}", e));
+                error.column = Some(start + 1);
+                error
+            })?;
+
+            let id = id_to_value.len();
+            id_to_value.push(value);
+            for x in start..x {
+                ids[(x, y)] = Some(id);
             }
         }
-        end_number!(max_x + 1, y);
     }
 
-    Ok(schematic)
+    Ok(Schematic {
+        ids,
+        id_to_value,
+        symbols,
+    })
 }
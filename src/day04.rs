@@ -2,92 +2,119 @@ use regex::Regex;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 
-use crate::utils;
+use crate::error::{parse_number, Error};
+use crate::solution::Solution;
 
 const NUM_RE: &str = r"([0-9]+)";
 const LINE_RE: &str = r"^Card +(?P<card_id>[0-9]+): (?P<winning>.*) \| (?P<available>.*)$";
 
-pub fn day04() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day04")? {
-        handle(run(&file));
+/// Day 4: Scratchcards
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<Card>;
+    type Answer = u32;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(input.as_bytes())
     }
-    Ok(())
-}
 
-fn handle(result: Result<(u32, u32), Box<dyn std::error::Error>>) {
-    match result {
-        Ok((sum_p1, sum_p2)) => println!("Result: Part1={} Part2={}", sum_p1, sum_p2),
-        Err(e) => println!("Error: {}", e),
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        Ok(input.iter().map(Card::points).sum())
     }
-    println!();
-}
 
-fn run(file: &str) -> Result<(u32, u32), Box<dyn std::error::Error>> {
-    println!("DAY04: {}", file);
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        Ok(count_cards(input))
+    }
+}
 
-    let file = std::fs::File::open(file)?;
-    let reader = std::io::BufReader::new(file);
+/// Scratchcard with its winning and available numbers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    pub winning: HashSet<u32>,
+    pub available: Vec<u32>,
+}
 
-    let result = count_nums(reader)?;
+impl Card {
+    /// Number of available numbers that are winning
+    pub fn matches(&self) -> u32 {
+        self.available
+            .iter()
+            .filter(|num| self.winning.contains(num))
+            .count() as u32
+    }
 
-    Ok(result)
+    /// Points of the card, doubled by every match after the first one
+    pub fn points(&self) -> u32 {
+        match self.matches() {
+            0 => 0,
+            count => 2_u32.pow(count - 1),
+        }
+    }
 }
 
-fn count_nums<R: std::io::BufRead>(reader: R) -> Result<(u32, u32), Box<dyn std::error::Error>> {
-    let mut sum_p1 = 0_u32;
-    let mut sum_p2 = 0_u32;
-
+/// Parse cards
+pub fn parse_input<R: std::io::BufRead>(reader: R) -> Result<Vec<Card>, Error> {
     let num_re = Regex::new(NUM_RE)?;
     let line_re = Regex::new(LINE_RE)?;
 
-    let mut multiplier_queue = VecDeque::new();
+    let mut cards = Vec::new();
 
-    for line in reader.lines() {
+    for (line_no, line) in reader.lines().enumerate() {
         let l = line?.to_owned();
 
         let captures = line_re.captures(l.as_str());
 
         if let Some(captures) = captures {
-            let (_, [_, winning, available]) = captures.extract();
+            let (_, [card_id, winning, available]) = captures.extract();
             let mut winning_nums = HashSet::new();
             for winning in num_re.captures_iter(winning) {
                 let (_, [num]) = winning.extract();
-                winning_nums.insert(num.parse::<u32>()?);
+                winning_nums.insert(parse_number::<u32>(line_no + 1, &l, num)?);
             }
 
-            let curr_multiplier = multiplier_queue.pop_front().unwrap_or(0) + 1;
-
-            let mut count = 0_u32;
+            let mut available_nums = Vec::new();
             for available in num_re.captures_iter(available) {
                 let (_, [num]) = available.extract();
-                if winning_nums.contains(&num.parse::<u32>()?) {
-                    count += 1;
-                }
+                available_nums.push(parse_number::<u32>(line_no + 1, &l, num)?);
             }
 
-            for i in 1..=count {
-                let curr = multiplier_queue.get((i - 1).try_into().unwrap());
-                if let Some(curr) = curr {
-                    multiplier_queue[(i - 1).try_into().unwrap()] = curr + curr_multiplier;
-                } else {
-                    multiplier_queue.push_back(curr_multiplier);
-                }
-            }
+            cards.push(Card {
+                id: parse_number::<u32>(line_no + 1, &l, card_id)?,
+                winning: winning_nums,
+                available: available_nums,
+            });
+        } else {
+            return Err(Error::parse(line_no + 1, &l, "Invalid card"));
+        }
+    }
 
-            // println!("{}: {} {} {:?}", l, count, curr_multiplier, multiplier_queue);
-            sum_p2 += curr_multiplier;
+    Ok(cards)
+}
+
+/// Total number of cards after every card wins copies of the following ones
+pub fn count_cards(cards: &[Card]) -> u32 {
+    let mut sum = 0_u32;
+    let mut multiplier_queue = VecDeque::new();
 
-            if count != 0 {
-                if count == 1 {
-                    sum_p1 += 1;
-                } else {
-                    sum_p1 += 2_u32.pow(count - 1);
-                }
+    for card in cards {
+        let curr_multiplier = multiplier_queue.pop_front().unwrap_or(0) + 1;
+
+        let count = card.matches() as usize;
+        for i in 0..count {
+            if let Some(curr) = multiplier_queue.get_mut(i) {
+                *curr += curr_multiplier;
+            } else {
+                multiplier_queue.push_back(curr_multiplier);
             }
-        } else {
-            return Err(format!("Invalid line: {}", l).into());
         }
+
+        // println!("{}: {} {} {:?}", card.id, count, curr_multiplier, multiplier_queue);
+        sum += curr_multiplier;
     }
 
-    Ok((sum_p1, sum_p2))
+    sum
 }
@@ -1,8 +1,11 @@
 use regex::Regex;
-use std::cmp;
 use std::collections::HashMap;
 
-use crate::utils;
+use crate::error::{parse_number, Error, ParseError};
+use crate::solution::{Part, Solution};
+use crate::utils::interval::IntervalSet;
+use crate::utils::range_map::{Mapping, RangeMap};
+use crate::utils::search;
 
 const INPUT_RE: &str = r"(?m)^(?P<name>[a-z]+)s: +(?P<values>[0-9 ]+)$";
 const NUM_RE: &str = r"([0-9]+)";
@@ -11,65 +14,106 @@ const MAPPING_LIST_RE: &str =
     r"(?P<name>[a-z]+)-to-(?P<name2>[a-z]+) map:\n(?P<mappings>[0-9 \n]*)(?:\n\n|$)";
 const MAPPING_RE: &str = r"(?m)^(?P<destination>[0-9]+) +(?P<source>[0-9]+) +(?P<length>[0-9]+)$";
 
-type ConversionMap = HashMap<String, HashMap<String, Vec<(u64, u64, u64)>>>;
+/// Category all values are converted to
+pub const TARGET_CATEGORY: &str = "location";
+
+/// Maps indexed by source and destination category
+pub type ConversionMap = HashMap<String, HashMap<String, RangeMap>>;
+
+/// Initial category and its values, read both ways the parts read them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InitialValues {
+    pub name: String,
+    pub values: Vec<u64>,
+    /// Each value on its own (part 1)
+    pub ranges: IntervalSet,
+    /// Values as pairs of `(start, length)` (part 2)
+    pub pair_ranges: IntervalSet,
+}
 
-pub fn day05() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day05")? {
-        handle(run(&file));
-    }
-    Ok(())
+/// Initial values and all conversion maps
+#[derive(Clone, Debug)]
+pub struct Almanac {
+    pub initial: InitialValues,
+    pub map: ConversionMap,
 }
 
-fn handle(result: Result<(u64, u64), Box<dyn std::error::Error>>) {
-    match result {
-        Ok((sum_p1, sum_p2)) => println!("Result: Part1={} Part2={}", sum_p1, sum_p2),
-        Err(e) => println!("Error: {}", e),
+/// Day 5: If You Give A Seed A Fertilizer
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = Almanac;
+    type Answer = u64;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let initial = parse_initial_input(&mut input.as_bytes())?;
+        let map = parse_map(input)?;
+        // println!("{:?}", initial);
+        // println!("{:?}", map);
+
+        Ok(Almanac { initial, map })
     }
-    println!();
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        let Almanac { initial, map } = input;
+
+        let location_map =
+            target_map(map, &initial.name).ok_or(Error::no_solution(5, Part::Part1))?;
+
+        // println!("{:?}", initial.ranges);
+        let values_p1 = location_map.image(&initial.ranges);
+        // println!("{:?}", values_p1);
+        let min_p1 = values_p1.min().ok_or(Error::no_solution(5, Part::Part1))?;
+
+        Ok(min_p1)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        let Almanac { initial, map } = input;
+
+        let location_map =
+            target_map(map, &initial.name).ok_or(Error::no_solution(5, Part::Part2))?;
+
+        // println!("{:?}", initial.pair_ranges);
+        let values_p2 = location_map.image(&initial.pair_ranges);
+        // println!("{:?}", values_p2);
+        let min_p2 = values_p2.min().ok_or(Error::no_solution(5, Part::Part2))?;
+
+        Ok(min_p2)
+    }
+}
+
+/// Shortest chain of categories converted through to get from `from` to `to`
+pub fn find_chain<'a>(map: &'a ConversionMap, from: &'a str, to: &'a str) -> Option<Vec<&'a str>> {
+    let search = search::bfs([from], |&name| {
+        map.get(name)
+            .into_iter()
+            .flat_map(|destinations| destinations.keys().map(String::as_str))
+    });
+    search.path(&to)
 }
 
-fn run(file: &str) -> Result<(u64, u64), Box<dyn std::error::Error>> {
-    println!("DAY05: {}", file);
-
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
-
-    let (name, values) = parse_initial_input(&mut reader)?;
-    let main_map = parse_map(&mut reader)?;
-    // println!("{:?}: {:?}", name, values);
-    // println!("{:?}", main_map);
-
-    let conversion_names: [&str; 8] = [
-        &name,
-        "soil",
-        "fertilizer",
-        "water",
-        "light",
-        "temperature",
-        "humidity",
-        "location",
-    ];
-
-    let ranges_p1 = as_ranges(&values);
-    // println!("{:?}", ranges_p1);
-    let values_p1 = convert_ranges_names(&main_map, &conversion_names, &ranges_p1);
-    // let values_p1_sorted = values_p1.iter().sorted().collect::<Vec<_>>();
-    // println!("{:?}", values_p1_sorted);
-    let min_p1 = min_value_ranges(&values_p1).ok_or("No values")?;
-
-    let ranges_p2 = to_ranges(&values);
-    // println!("{:?}", ranges_p2);
-    let values_p2 = convert_ranges_names(&main_map, &conversion_names, &ranges_p2);
-    // let values_p2_sorted = values_p2.iter().sorted().collect::<Vec<_>>();
-    // println!("{:?}", values_p2_sorted);
-    let min_p2 = min_value_ranges(&values_p2).ok_or("No values")?;
-
-    Ok((min_p1, min_p2))
+/// Single map converting values through all categories of `chain`
+pub fn chain_map(map: &ConversionMap, chain: &[&str]) -> RangeMap {
+    chain
+        .windows(2)
+        .fold(RangeMap::identity(), |composed, pair| {
+            composed.compose(&map[pair[0]][pair[1]])
+        })
 }
 
-fn parse_initial_input<R: std::io::BufRead>(
-    reader: &mut R,
-) -> Result<(String, Vec<u64>), Box<dyn std::error::Error>> {
+/// Single map converting values of category `name` to the target category
+pub fn target_map(map: &ConversionMap, name: &str) -> Option<RangeMap> {
+    let chain = find_chain(map, name, TARGET_CATEGORY)?;
+    Some(chain_map(map, &chain))
+}
+
+/// Parse the first line with initial category name and its values
+///
+/// Fails if the values don't fit into `u64` when read as ranges.
+pub fn parse_initial_input<R: std::io::BufRead>(reader: &mut R) -> Result<InitialValues, Error> {
     let input_re = Regex::new(INPUT_RE)?;
     let num_re = Regex::new(NUM_RE)?;
 
@@ -81,145 +125,172 @@ fn parse_initial_input<R: std::io::BufRead>(
     if let Some(captures) = captures {
         let (_, [name, values]) = captures.extract();
         let name = name.to_owned();
-        let mut values_list = Vec::new();
-
-        for num in num_re.captures_iter(values) {
-            let (_, [num]) = num.extract();
-            values_list.push(num.parse::<u64>()?);
-        }
-
-        return Ok((name, values_list));
+        let texts: Vec<regex::Match> = num_re.find_iter(values).collect();
+        let values_list = texts
+            .iter()
+            .map(|num| parse_number::<u64>(1, &line, num.as_str()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let overflow = |text: &str| ParseError::within(1, &line, text, "Range overflows u64");
+        let ranges = as_ranges(&values_list).ok_or_else(|| {
+            let i = values_list.iter().position(|v| v.checked_add(1).is_none());
+            overflow(texts[i.unwrap_or_default()].as_str())
+        })?;
+        let pair_ranges = to_ranges(&values_list).ok_or_else(|| {
+            let i = values_list
+                .chunks_exact(2)
+                .position(|pair| pair[0].checked_add(pair[1]).is_none())
+                .unwrap_or_default();
+            // Error points at both values of the pair
+            overflow(&values[texts[2 * i].start()..texts[2 * i + 1].end()])
+        })?;
+
+        return Ok(InitialValues {
+            name,
+            values: values_list,
+            ranges,
+            pair_ranges,
+        });
     }
 
-    Err("Invalid input".into())
+    Err(Error::parse(1, line.trim_end(), "Invalid initial values"))
 }
 
-fn parse_map<R: std::io::BufRead>(
-    reader: &mut R,
-) -> Result<ConversionMap, Box<dyn std::error::Error>> {
+/// Mappings by source and destination category, together with their text
+type MappingLists<'a> = HashMap<String, HashMap<String, Vec<(Mapping, &'a str)>>>;
+
+/// Parse all `X-to-Y map:` blocks of the input
+pub fn parse_map(input: &str) -> Result<ConversionMap, Error> {
     let mapping_list_re = Regex::new(MAPPING_LIST_RE)?;
     let mapping_re = Regex::new(MAPPING_RE)?;
 
-    let mut map = HashMap::new();
+    // Mappings are kept with their text to report overlaps
+    let mut mapping_lists: MappingLists = HashMap::new();
 
-    let mut input = String::new();
-    reader.read_to_string(&mut input)?;
+    let parse_number = |text: &str| {
+        text.parse::<u64>()
+            .map_err(|e| ParseError::within_input(input, text, format!("Invalid number: {}", e)))
+    };
 
-    for captures in mapping_list_re.captures_iter(input.as_str()) {
+    for captures in mapping_list_re.captures_iter(input) {
         let (_, [name, name2, mappings]) = captures.extract();
         let name = name.to_owned();
         let name2 = name2.to_owned();
 
-        let source_map: &mut HashMap<String, Vec<(u64, u64, u64)>> = map.entry(name).or_default();
-        let destination_map = source_map.entry(name2).or_default();
+        let destination_map = mapping_lists
+            .entry(name)
+            .or_default()
+            .entry(name2)
+            .or_default();
 
         for mapping in mapping_re.captures_iter(mappings) {
-            let (_, [destination, source, length]) = mapping.extract();
-            let destination = destination.parse::<u64>()?;
-            let source = source.parse::<u64>()?;
-            let length = length.parse::<u64>()?;
+            let (text, [destination, source, length]) = mapping.extract();
+            let destination = parse_number(destination)?;
+            let source = parse_number(source)?;
+            let length = parse_number(length)?;
+
+            if source.checked_add(length).is_none() || destination.checked_add(length).is_none() {
+                return Err(ParseError::within_input(input, text, "Mapping overflows u64").into());
+            }
+
+            let mapping = Mapping {
+                source,
+                destination,
+                length,
+            };
+
+            // Checked in input order, so the first conflict in the input is reported
+            if let Some(other) = find_overlap(destination_map, &mapping) {
+                let other_line = ParseError::within_input(input, other, "").line;
+                let message = format!("Mapping overlaps mapping on line {}", other_line);
+                return Err(ParseError::within_input(input, text, message).into());
+            }
+            destination_map.push((mapping, text));
+        }
+    }
 
-            destination_map.push((source, destination, length));
+    let mut map = ConversionMap::new();
+    for (name, destinations) in mapping_lists {
+        for (name2, mappings) in destinations {
+            let mappings = mappings.into_iter().map(|(mapping, _)| mapping).collect();
+            map.entry(name.clone())
+                .or_default()
+                .insert(name2, RangeMap::new(mappings));
         }
     }
 
     Ok(map)
 }
 
-fn as_range(value: u64) -> (u64, u64) {
-    (value, 1)
-}
-
-fn as_ranges(values: &[u64]) -> Vec<(u64, u64)> {
-    values.iter().map(|value| as_range(*value)).collect()
+/// Text of the first of `mappings` whose source range overlaps the one of `mapping`
+fn find_overlap<'a>(mappings: &[(Mapping, &'a str)], mapping: &Mapping) -> Option<&'a str> {
+    let overlaps = |other: &Mapping| {
+        other.source < mapping.source + mapping.length
+            && mapping.source < other.source + other.length
+    };
+    mappings
+        .iter()
+        .find(|(other, _)| overlaps(other))
+        .map(|&(_, text)| text)
 }
 
-fn to_ranges(values: &[u64]) -> Vec<(u64, u64)> {
+/// Treat each value as a range of length 1, `None` if some range doesn't fit into `u64`
+pub fn as_ranges(values: &[u64]) -> Option<IntervalSet> {
     values
         .iter()
-        .step_by(2)
-        .zip(values.iter().skip(1).step_by(2))
-        .map(move |(start, length)| (*start, *length))
+        .map(|&value| Some(value..value.checked_add(1)?))
         .collect()
 }
 
-fn convert_range<'a>(
-    map: &'a ConversionMap,
-    name_from: &'a str,
-    name_to: &'a str,
-    range: (u64, u64),
-) -> impl Iterator<Item = (u64, u64)> + 'a {
-    let (start, length) = range;
-    let end = start + length;
-
-    let mut missing = vec![range];
-
-    let converted: Vec<(u64, u64)> = map[name_from][name_to]
-        .iter()
-        .filter(move |(source, _, length)| !(*source + *length <= start || *source >= end))
-        .map(|(source, destination, length)| {
-            let n_start = cmp::max(start, *source);
-            let n_end = cmp::min(end, *source + *length);
-            let n_length = n_end - n_start;
-            let n_offset = n_start - *source;
-
-            missing = missing
-                .iter()
-                .flat_map(|(s, l)| {
-                    let e = *s + *l;
-                    let mut new_missing = Vec::new();
-                    if e <= n_start || *s >= n_end {
-                        // No overlap
-                        new_missing.push((*s, *l));
-                    } else {
-                        if *s < n_start {
-                            new_missing.push((*s, n_start - *s));
-                        }
-                        if e > n_end {
-                            new_missing.push((n_end, e - n_end));
-                        }
-                    }
-                    new_missing.into_iter()
-                })
-                .collect();
-
-            // println!("{}: {:?} --> {:?} -> {:?} <- {:?}", name_from, range, (*destination + n_offset, n_length), missing, (n_start, n_length, n_offset, (*source, *destination, *length)));
-            // println!("{:?} => {:?}", (*source, *destination, *length), (*destination + n_offset, n_length));
-            (*destination + n_offset, n_length)
-        })
-        .collect();
-
-    // println!("{}: {:?} -> {:?} -> {:?}", name_from, range, converted, missing);
-    converted.into_iter().chain(missing.into_iter())
+/// Treat values as pairs of `(start, length)`, `None` if some range doesn't fit into `u64`
+pub fn to_ranges(values: &[u64]) -> Option<IntervalSet> {
+    values
+        .chunks_exact(2)
+        .map(|pair| Some(pair[0]..pair[0].checked_add(pair[1])?))
+        .collect()
 }
 
-fn convert_ranges<'a>(
-    map: &'a ConversionMap,
-    name_from: &'a str,
-    name_to: &'a str,
-    ranges: &[(u64, u64)],
-) -> Vec<(u64, u64)> {
-    ranges
-        .iter()
-        .flat_map(|range| convert_range(map, name_from, name_to, *range))
-        .collect()
+/// Values of `sources` that end up in `targets` after converting through `chain`
+pub fn find_sources(
+    map: &ConversionMap,
+    chain: &[&str],
+    sources: &IntervalSet,
+    targets: &IntervalSet,
+) -> IntervalSet {
+    let preimage = chain_map(map, chain).preimage(targets);
+    preimage.intersection(sources)
 }
 
-fn convert_ranges_names(
+/// Lowest value `sources` convert to through `chain` and the sources converting to it
+pub fn min_sources(
     map: &ConversionMap,
-    names: &[&str],
-    ranges: &[(u64, u64)],
-) -> Vec<(u64, u64)> {
-    names
-        .iter()
-        .zip(names.iter().skip(1))
-        .fold(ranges.to_vec(), |ranges, (name_from, name_to)| {
-            convert_ranges(map, name_from, name_to, &ranges)
-        })
+    chain: &[&str],
+    sources: &IntervalSet,
+) -> Option<(u64, IntervalSet)> {
+    let composed = chain_map(map, chain);
+    let min = composed.image(sources).min()?;
+    let preimage = composed.preimage(&IntervalSet::from(min..min + 1));
+    Some((min, preimage.intersection(sources)))
 }
 
-fn min_value_ranges(ranges: &[(u64, u64)]) -> Option<u64> {
-    ranges.iter().map(|(value, _)| *value).min()
+/// Value in every category of `chain`, starting with `value` in the first one
+///
+/// Stops early if the value falls out of `u64`.
+pub fn trace<'a>(map: &ConversionMap, chain: &[&'a str], value: u64) -> Vec<(&'a str, u64)> {
+    let mut trace = Vec::new();
+    let mut value = value;
+
+    for (i, &name) in chain.iter().enumerate() {
+        if i > 0 {
+            let Some(next) = map[chain[i - 1]][name].get(value) else {
+                break;
+            };
+            value = next;
+        }
+        trace.push((name, value));
+    }
+
+    trace
 }
 
 // Previous Part1 only implementation
@@ -258,3 +329,51 @@ fn min_value_ranges(ranges: &[(u64, u64)]) -> Option<u64> {
 //         convert_values(map, name_from, name_to, &values)
 //     })
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(input: &str) -> ParseError {
+        match Day05.parse(input) {
+            Err(Error::Parse(error)) => error,
+            result => panic!("Expected parse error, got {:?}", result.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn overlaps_are_reported_in_input_order() {
+        let input = "seeds: 1\n\
+                     \n\
+                     seed-to-location map:\n\
+                     50 10 5\n\
+                     60 20 5\n\
+                     70 12 10\n\
+                     80 22 1\n";
+        // Both later lines overlap earlier ones, the first of them is reported
+        // and points at the first mapping it overlaps
+        for _ in 0..5 {
+            let error = parse_error(input);
+            assert_eq!((error.line, error.text.as_str()), (6, "70 12 10"));
+            assert_eq!(error.message, "Mapping overlaps mapping on line 4");
+        }
+    }
+
+    #[test]
+    fn overflowing_seed_points_at_its_value() {
+        let error = parse_error("seeds: 5 18446744073709551615\n");
+        assert_eq!(error.line, 1);
+        assert_eq!(error.column, Some(10));
+        assert_eq!(error.text, "18446744073709551615");
+        assert_eq!(error.message, "Range overflows u64");
+    }
+
+    #[test]
+    fn overflowing_seed_range_points_at_both_values() {
+        let error = parse_error("seeds: 79 14 18446744073709551610 13\n");
+        assert_eq!(error.line, 1);
+        assert_eq!(error.column, Some(14));
+        assert_eq!(error.text, "18446744073709551610 13");
+        assert_eq!(error.message, "Range overflows u64");
+    }
+}
@@ -1,45 +1,45 @@
 use regex::Regex;
 
-use crate::utils;
+use crate::error::Error;
+use crate::solution::{Part, Solution};
 
 const NUM_RE: &str = r"([0-9]+)";
 const INPUT_RE: &str = r"^Time: *(?P<times>.*)\nDistance: *(?P<distances>.*)\n*$";
 
-type Race = (u64, u64);
+/// Race `(time, distance)`
+pub type Race = (u64, u64);
 
-pub fn day06() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day06")? {
-        handle(run(&file, false));
-        handle(run(&file, true));
-    }
-    Ok(())
+/// Races of the sheet, and the single race part 2 reads when ignoring spaces between digits
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Races {
+    pub races: Vec<Race>,
+    pub joined: Race,
 }
 
-fn handle(result: Result<u64, Box<dyn std::error::Error>>) {
-    match result {
-        Ok(result) => println!("Result: {}", result),
-        Err(e) => println!("Error: {}", e),
-    }
-    println!();
-}
+/// Day 6: Wait For It
+pub struct Day06;
 
-fn run(file: &str, part2: bool) -> Result<u64, Box<dyn std::error::Error>> {
-    println!("DAY06: Part{}: {}", if part2 { "2" } else { "1" }, file);
+impl Solution for Day06 {
+    const DAY: u8 = 6;
 
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
+    type Input = Races;
+    type Answer = u64;
 
-    let races = parse_input(&mut reader, part2)?;
-    // println!("{:?}", races);
-    let result = solve(&races);
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(&mut input.as_bytes())
+    }
 
-    Ok(result)
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve(&input.races).ok_or(Error::no_solution(6, Part::Part1))
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve(&[input.joined]).ok_or(Error::no_solution(6, Part::Part2))
+    }
 }
 
-fn parse_input<R: std::io::BufRead>(
-    reader: &mut R,
-    part2: bool,
-) -> Result<Vec<Race>, Box<dyn std::error::Error>> {
+/// Parse races as listed, and as a single race with spaces between digits removed
+pub fn parse_input<R: std::io::BufRead>(reader: &mut R) -> Result<Races, Error> {
     let input_re = Regex::new(INPUT_RE)?;
     let num_re = Regex::new(NUM_RE)?;
 
@@ -51,56 +51,66 @@ fn parse_input<R: std::io::BufRead>(
     if let Some(captures) = captures {
         let (_, [times, distances]) = captures.extract();
 
-        let mut times = times.to_string();
-        let mut distances = distances.to_string();
-
-        if part2 {
-            // Pre-process times and distances by removing spaces
-            times = times.replace(' ', "");
-            distances = distances.replace(' ', "");
-        }
-
-        let mut times_list = Vec::new();
-        let mut distances_list = Vec::new();
-
-        for num in num_re.captures_iter(times.as_str()) {
-            let (_, [num]) = num.extract();
-            times_list.push(num.parse::<u64>()?);
-        }
-
-        for num in num_re.captures_iter(distances.as_str()) {
-            let (_, [num]) = num.extract();
-            distances_list.push(num.parse::<u64>()?);
+        let parse_list = |line: usize, text: &str, what: &str| -> Result<Vec<u64>, Error> {
+            num_re
+                .captures_iter(text)
+                .map(|num| {
+                    let (_, [num]) = num.extract();
+                    num.parse::<u64>()
+                        .map_err(|e| Error::parse(line, num, format!("Invalid {}: {}", what, e)))
+                })
+                .collect()
+        };
+
+        let times_list = parse_list(1, times, "time")?;
+        let distances_list = parse_list(2, distances, "distance")?;
+
+        if times_list.len() != distances_list.len() {
+            return Err(Error::parse(
+                2,
+                distances,
+                format!(
+                    "Expected {} distances, found {}",
+                    times_list.len(),
+                    distances_list.len()
+                ),
+            ));
         }
 
-        assert_eq!(times_list.len(), distances_list.len());
+        // Pre-process times and distances by removing spaces
+        let time = parse_list(1, &times.replace(' ', ""), "time")?;
+        let distance = parse_list(2, &distances.replace(' ', ""), "distance")?;
+        let joined = match (&time[..], &distance[..]) {
+            (&[time], &[distance]) => (time, distance),
+            _ => return Err(Error::parse(1, times, "Invalid races")),
+        };
 
-        let races = times_list
-            .into_iter()
-            .zip(distances_list.into_iter())
-            .collect();
+        let races = times_list.into_iter().zip(distances_list).collect();
 
-        return Ok(races);
+        return Ok(Races { races, joined });
     }
 
-    Err("Invalid input".into())
+    Err(Error::parse(
+        1,
+        input.lines().next().unwrap_or_default(),
+        "Invalid races",
+    ))
 }
 
-fn solve(races: &[Race]) -> u64 {
+/// Product of the number of ways to win each race, `None` if some race can't be won
+pub fn solve(races: &[Race]) -> Option<u64> {
     races
         .iter()
         .map(|(time, distance)| solution_range(*time, *distance))
-        .map(|(from, to)| to - from + 1)
-        .filter(|x| *x > 0)
+        .map(|range| range.map(|(from, to)| to - from + 1))
         .product()
 }
 
-fn solution_range(time: u64, distance: u64) -> (u64, u64) {
-    let distance = distance + 1; // We need to travel at least one more than the distance
+/// First and last button hold time that beats the distance, `None` if no hold time does
+pub fn solution_range(time: u64, distance: u64) -> Option<(u64, u64)> {
+    let distance = distance as u128 + 1; // We need to travel at least one more than the distance
 
-    let d = time * time - 4 * distance;
-    // assert!(d >= 0); // No need for this check
-    // u64 is always positive and Rust will error out if underflow occurs
+    let d = (time as u128 * time as u128).checked_sub(4 * distance)?;
     let d = d as f64;
     let d = d.sqrt();
     let d = d / 2.0;
@@ -126,5 +136,6 @@ fn solution_range(time: u64, distance: u64) -> (u64, u64) {
     // }
 
     // println!("{} {} - {} {} - {}", time, distance, from, to, to - from + 1);
-    (from, to)
+    // Without an integer between the roots the race can't be won either
+    (from <= to).then_some((from, to))
 }
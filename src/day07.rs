@@ -1,61 +1,92 @@
 use itertools::Itertools;
 use regex::Regex;
 
-use crate::utils;
+use crate::error::{parse_number, Error, ParseError};
+use crate::solution::{Part, Solution};
 
 const GAME_RE: &str = r"^(?P<cards>[2-9TJQKA]{5}) (?P<bet>[0-9]+)$";
 
-type Card = u64;
-type Cards = [Card; 5];
-type Bet = u64;
-type Amount = u64;
-type Amounts = [Amount; 15];
-type Type = u64;
-type Game = (Cards, Bet, Type);
-
-pub fn day07() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day07")? {
-        handle(run(&file, false));
-        handle(run(&file, true));
-    }
-    Ok(())
+pub type Card = u64;
+pub type Cards = [Card; 5];
+pub type Bet = u64;
+pub type Amount = u64;
+pub type Amounts = [Amount; 15];
+pub type Type = u64;
+/// Cards in hand, its bet and type (higher is better)
+pub type Game = (Cards, Bet, Type);
+
+/// Hand as written in the input, before its cards are valued by either part
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hand {
+    pub cards: [char; 5],
+    pub bet: Bet,
 }
 
-fn handle(result: Result<u64, Box<dyn std::error::Error>>) {
-    match result {
-        Ok(result) => println!("Result: {}", result),
-        Err(e) => println!("Error: {}", e),
+impl Hand {
+    /// Value the cards and type of the hand, part 2 treats `J` as the weakest joker
+    pub fn game(&self, part2: bool) -> Option<Game> {
+        let cards: Cards = self
+            .cards
+            .iter()
+            .map(|&c| parse_card(c, part2))
+            .collect::<Option<Vec<Card>>>()?
+            .try_into()
+            .ok()?;
+        let amounts: Amounts =
+            std::array::from_fn(|v| cards.iter().filter(|c| **c == v as Card).count() as Amount);
+
+        Some((cards, self.bet, calculate_type(amounts)?))
     }
-    println!();
 }
 
-fn run(file: &str, part2: bool) -> Result<u64, Box<dyn std::error::Error>> {
-    println!("DAY07: Part{}: {}", if part2 { "2" } else { "1" }, file);
+/// Day 7: Camel Cards
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Input = Vec<Hand>;
+    type Answer = u64;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(input.as_bytes())
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        run(input, Part::Part1)
+    }
 
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        run(input, Part::Part2)
+    }
+}
 
-    let games = parse_input(&mut reader, part2)?;
+fn run(hands: &[Hand], part: Part) -> Result<u64, Error> {
+    let games = hands
+        .iter()
+        .map(|hand| hand.game(part == Part::Part2))
+        .collect::<Option<Vec<Game>>>()
+        .ok_or(Error::no_solution(7, part))?;
     // println!("{:?}", games);
     let result = solve(&games);
 
     Ok(result)
 }
 
-fn parse_card(c: char, part2: bool) -> Result<Card, Box<dyn std::error::Error>> {
+fn parse_card(c: char, part2: bool) -> Option<Card> {
     match (c, part2) {
-        ('2'..='9', _) => Ok(c.to_digit(10).unwrap() as Card),
-        ('T', _) => Ok(10),
-        ('J', false) => Ok(11),
-        ('J', true) => Ok(1),
-        ('Q', _) => Ok(12),
-        ('K', _) => Ok(13),
-        ('A', _) => Ok(14),
-        _ => Err(format!("Invalid card: {}", c).into()),
+        ('2'..='9', _) => c.to_digit(10).map(|v| v as Card),
+        ('T', _) => Some(10),
+        ('J', false) => Some(11),
+        ('J', true) => Some(1),
+        ('Q', _) => Some(12),
+        ('K', _) => Some(13),
+        ('A', _) => Some(14),
+        _ => None,
     }
 }
 
-fn calculate_type(amounts: Amounts) -> Result<Type, Box<dyn std::error::Error>> {
+fn calculate_type(amounts: Amounts) -> Option<Type> {
     let jokers = amounts[1];
     let best: [Amount; 5] = amounts
         .into_iter()
@@ -65,7 +96,7 @@ fn calculate_type(amounts: Amounts) -> Result<Type, Box<dyn std::error::Error>>
         .take(5)
         .collect::<Vec<Amount>>()
         .try_into()
-        .unwrap();
+        .ok()?;
 
     // 7 - 5 of a kind
     // 6 - 4 of a kind
@@ -76,67 +107,67 @@ fn calculate_type(amounts: Amounts) -> Result<Type, Box<dyn std::error::Error>>
     // 1 - High card
 
     match (jokers, best) {
-        (5, [_, _, _, _, _]) => Ok(7),
-        (4, [1, _, _, _, _]) => Ok(7),
-        (3, [2, _, _, _, _]) => Ok(7),
-        (3, [1, 1, _, _, _]) => Ok(6),
-        (2, [3, _, _, _, _]) => Ok(7),
-        (2, [2, 1, _, _, _]) => Ok(6),
-        (2, [1, 1, 1, _, _]) => Ok(4),
-        (1, [4, _, _, _, _]) => Ok(7),
-        (1, [3, 1, _, _, _]) => Ok(6),
-        (1, [2, 2, _, _, _]) => Ok(5),
-        (1, [2, 1, 1, _, _]) => Ok(4),
-        (1, [1, 1, 1, 1, _]) => Ok(2),
-        (0, [5, _, _, _, _]) => Ok(7),
-        (0, [4, 1, _, _, _]) => Ok(6),
-        (0, [3, 2, _, _, _]) => Ok(5),
-        (0, [3, 1, 1, _, _]) => Ok(4),
-        (0, [2, 2, 1, _, _]) => Ok(3),
-        (0, [2, 1, 1, 1, _]) => Ok(2),
-        (0, [1, 1, 1, 1, 1]) => Ok(1),
-        _ => Err(format!("Invalid amounts: {:?}", best).into()),
+        (5, [_, _, _, _, _]) => Some(7),
+        (4, [1, _, _, _, _]) => Some(7),
+        (3, [2, _, _, _, _]) => Some(7),
+        (3, [1, 1, _, _, _]) => Some(6),
+        (2, [3, _, _, _, _]) => Some(7),
+        (2, [2, 1, _, _, _]) => Some(6),
+        (2, [1, 1, 1, _, _]) => Some(4),
+        (1, [4, _, _, _, _]) => Some(7),
+        (1, [3, 1, _, _, _]) => Some(6),
+        (1, [2, 2, _, _, _]) => Some(5),
+        (1, [2, 1, 1, _, _]) => Some(4),
+        (1, [1, 1, 1, 1, _]) => Some(2),
+        (0, [5, _, _, _, _]) => Some(7),
+        (0, [4, 1, _, _, _]) => Some(6),
+        (0, [3, 2, _, _, _]) => Some(5),
+        (0, [3, 1, 1, _, _]) => Some(4),
+        (0, [2, 2, 1, _, _]) => Some(3),
+        (0, [2, 1, 1, 1, _]) => Some(2),
+        (0, [1, 1, 1, 1, 1]) => Some(1),
+        _ => None,
     }
 }
 
-fn parse_input<R: std::io::BufRead>(
-    reader: R,
-    part2: bool,
-) -> Result<Vec<Game>, Box<dyn std::error::Error>> {
+/// Parse hands
+pub fn parse_input<R: std::io::BufRead>(reader: R) -> Result<Vec<Hand>, Error> {
     let game_re = Regex::new(GAME_RE)?;
 
-    let mut games = Vec::new();
+    let mut hands = Vec::new();
 
-    for line in reader.lines() {
+    for (i, line) in reader.lines().enumerate() {
         let line = line?;
         let captures = game_re.captures(&line);
 
         if let Some(captures) = captures {
-            let (_, [cards, bet]) = captures.extract();
-
+            let (_, [cards_text, bet]) = captures.extract();
+
+            let cards = cards_text
+                .char_indices()
+                .map(|(j, c)| {
+                    // Jokers are valid cards in both parts, only their value differs
+                    parse_card(c, false).map(|_| c).ok_or_else(|| {
+                        ParseError::within(i + 1, &line, &cards_text[j..j + 1], "Invalid card")
+                    })
+                })
+                .collect::<Result<Vec<char>, _>>()?;
             let cards = cards
-                .chars()
-                .map(|c| parse_card(c, part2))
-                .collect::<Result<Vec<Card>, _>>()?;
-            assert_eq!(cards.len(), 5);
-            let bet = bet.parse::<Bet>()?;
-            let amounts = (0..15)
-                .map(|v| cards.iter().filter(|c| **c == v).map(|_| 1).sum())
-                .collect::<Vec<Amount>>();
-            assert_eq!(amounts.len(), 15);
-
-            let cards = cards.try_into().unwrap();
-            let amounts = amounts.try_into().unwrap();
-            let type_ = calculate_type(amounts)?;
-
-            games.push((cards, bet, type_));
+                .try_into()
+                .map_err(|_| ParseError::within(i + 1, &line, cards_text, "Expected 5 cards"))?;
+            let bet = parse_number::<Bet>(i + 1, &line, bet)?;
+
+            hands.push(Hand { cards, bet });
+        } else if !line.trim().is_empty() {
+            return Err(Error::parse(i + 1, &line, "Invalid hand"));
         }
     }
 
-    Ok(games)
+    Ok(hands)
 }
 
-fn solve(games: &[Game]) -> u64 {
+/// Total winnings of all hands
+pub fn solve(games: &[Game]) -> u64 {
     games
         .iter()
         .sorted_by(|(cards1, _, type1), (cards2, _, type2)| {
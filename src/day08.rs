@@ -1,74 +1,198 @@
+use glob::Pattern;
 use itertools::Itertools;
+use num_integer::{ExtendedGcd, Integer};
 use regex::Regex;
+use std::fmt::{self, Display, Write};
+use std::str::FromStr;
 use std::{collections::HashMap, io::BufRead};
 
-use crate::utils;
+use crate::error::Error;
+use crate::solution::{Part, Solution};
+use crate::utils::{cycle, search};
 
 const PATH_RE: &str = r"(?m)^(?P<path>[LR]*)$";
 const NODE_RE: &str = r"^(?P<name>[^ ]*) *= *\((?P<left>[^ ]*), *(?P<right>[^ ]*)\)$";
 
-// type NodeMap = std::collections::HashMap<usize, String>;
-type Node = (usize, usize);
-type Nodes = Vec<Node>;
+/// Left and right neighbor of a node
+pub type Node = (usize, usize);
+pub type Nodes = Vec<Node>;
+/// Position in the path and the current node
+pub type State = (usize, usize);
 
+/// Parsed nodes with their names, start node ids and end node ids
 #[derive(Debug)]
-struct ActorCycle {
-    offset: u64,
-    length: u64,
-    prefix_end_indexes: Vec<u64>,
-    loop_end_indexes: Vec<u64>,
+pub struct Network {
+    pub names: Vec<String>,
+    /// Input line every node is defined on
+    pub lines: Vec<usize>,
+    pub nodes: Nodes,
+    pub start_ids: Vec<usize>,
+    pub end_ids: Vec<usize>,
 }
 
-pub fn day08() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day08")? {
-        handle(run(&file, false));
-        handle(run(&file, true));
+/// Node names matching any of a list of glob patterns
+///
+/// Parsed from a comma separated list like `AAA` or `*A,XYZ`,
+/// so plain names and patterns can be mixed.
+#[derive(Clone, Debug)]
+pub struct NodeSet(Vec<Pattern>);
+
+impl NodeSet {
+    /// Set of exactly the listed names
+    pub fn names<'a>(names: impl IntoIterator<Item = &'a str>) -> Self {
+        let patterns = names
+            .into_iter()
+            .map(|name| Pattern::new(&Pattern::escape(name)).unwrap())
+            .collect();
+        Self(patterns)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|pattern| pattern.matches(name))
     }
-    Ok(())
 }
 
-fn handle(result: Result<u64, Box<dyn std::error::Error>>) {
-    match result {
-        Ok(result) => println!("Result: {}", result),
-        Err(e) => println!("Error: {}", e),
+impl FromStr for NodeSet {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let patterns = s
+            .split(',')
+            .map(|pattern| Pattern::new(pattern.trim()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self(patterns))
     }
-    println!();
 }
 
-fn run(file: &str, part2: bool) -> Result<u64, Box<dyn std::error::Error>> {
-    println!("DAY08: Part{}: {}", if part2 { "2" } else { "1" }, file);
+/// Start and end nodes of the given part
+pub fn part_nodes(part: Part) -> (NodeSet, NodeSet) {
+    match part {
+        Part::Part1 => (NodeSet::names(["AAA"]), NodeSet::names(["ZZZ"])),
+        Part::Part2 => ("*A".parse().unwrap(), "*Z".parse().unwrap()),
+    }
+}
 
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
+/// Steps at which a ghost visits end nodes before and during its loop
+///
+/// Loop starts after `offset` steps and repeats every `length` steps,
+/// `loop_end_indexes` are relative to its start.
+#[derive(Debug)]
+pub struct ActorCycle {
+    pub offset: u64,
+    pub length: u64,
+    pub prefix_end_indexes: Vec<u64>,
+    pub loop_end_indexes: Vec<u64>,
+}
 
-    let path = parse_path(&mut reader)?;
+impl ActorCycle {
+    /// Whether the ghost stands on an end node after `step` steps
+    pub fn visits_end(&self, step: u64) -> bool {
+        if step < self.offset {
+            self.prefix_end_indexes.binary_search(&step).is_ok()
+        } else {
+            let index = (step - self.offset) % self.length;
+            self.loop_end_indexes.binary_search(&index).is_ok()
+        }
+    }
+}
 
-    let mut empty_line = String::new();
-    reader.read_line(&mut empty_line)?;
-    assert_eq!(empty_line, "\n");
+/// Day 8: Haunted Wasteland
+pub struct Day08;
 
-    let (nodes, start_ids, end_ids) = parse_nodes(&mut reader, part2)?;
+impl Solution for Day08 {
+    const DAY: u8 = 8;
 
-    // println!("{:?}", path);
-    // println!("{:?}", nodes);
-    // println!("{:?}", node_map);
+    type Input = Map;
+    type Answer = u64;
 
-    let cycles = start_ids
-        .iter()
-        .map(|&start_id| find_cycle(&nodes, &path, start_id, &end_ids))
-        .collect_vec();
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        run(input, Part::Part1)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        run(input, Part::Part2)
+    }
+}
+
+fn run(map: &Map, part: Part) -> Result<u64, Error> {
+    let (starts, ends) = part_nodes(part);
+    let network = map.network(&starts, &ends)?;
+
+    // println!("{:?}", map.path);
+    // println!("{:?}", network);
+
+    let cycles = find_cycles(&map.path, &network);
 
     // println!("{:?}", cycles);
 
-    let result = solve(&cycles).ok_or("No solution")?;
+    let result = solve(&cycles).ok_or(Error::no_solution(8, part))?;
     // let result = 0;
 
     Ok(result)
 }
 
-fn parse_path<R: std::io::BufRead>(
-    reader: &mut R,
-) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+/// Path and node definitions, start and end nodes are picked later
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Map {
+    pub path: Vec<usize>,
+    pub definitions: Vec<Definition>,
+}
+
+impl Map {
+    /// Resolve the definitions into a network with the given start and end nodes
+    ///
+    /// Undefined or duplicate nodes are reported as the error of the first such line.
+    pub fn network(&self, starts: &NodeSet, ends: &NodeSet) -> Result<Network, Error> {
+        resolve(&self.definitions, starts, ends)
+            .map_err(|diagnostics| diagnostics.into_iter().next().unwrap().into())
+    }
+}
+
+/// Parse path and node definitions
+pub fn parse_input(input: &str) -> Result<Map, Error> {
+    let mut reader = input.as_bytes();
+
+    let path = parse_header(&mut reader)?;
+    let definitions = parse_definitions(&mut reader)?;
+
+    Ok(Map { path, definitions })
+}
+
+/// Parse path and the node network with the given start and end nodes
+pub fn parse_map(
+    input: &str,
+    starts: &NodeSet,
+    ends: &NodeSet,
+) -> Result<(Vec<usize>, Network), Error> {
+    let map = parse_input(input)?;
+    let network = map.network(starts, ends)?;
+
+    Ok((map.path, network))
+}
+
+/// Parse path and the empty line following it
+fn parse_header<R: std::io::BufRead>(reader: &mut R) -> Result<Vec<usize>, Error> {
+    let path = parse_path(reader)?;
+
+    let mut empty_line = String::new();
+    reader.read_line(&mut empty_line)?;
+    if empty_line != "\n" {
+        return Err(Error::parse(
+            2,
+            empty_line.trim_end(),
+            "Expected empty line after path",
+        ));
+    }
+
+    Ok(path)
+}
+
+/// Parse L/R instructions as `0`/`1`
+pub fn parse_path<R: std::io::BufRead>(reader: &mut R) -> Result<Vec<usize>, Error> {
     let path_re = Regex::new(PATH_RE)?;
 
     let mut input = String::new();
@@ -86,177 +210,496 @@ fn parse_path<R: std::io::BufRead>(
 
         Ok(path)
     } else {
-        Err(format!("Invalid input: {}", input).into())
+        Err(Error::parse(1, input.trim_end(), "Invalid path"))
     }
 }
 
-fn parse_nodes<R: std::io::BufRead>(
-    reader: &mut R,
-    part2: bool,
-) -> Result<(Nodes, Vec<usize>, Vec<usize>), Box<dyn std::error::Error>> {
+/// Node as written in the input, before names are resolved
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Definition {
+    pub line: usize,
+    pub name: String,
+    pub left: String,
+    pub right: String,
+}
+
+fn parse_definitions<R: std::io::BufRead>(reader: &mut R) -> Result<Vec<Definition>, Error> {
     let node_re = Regex::new(NODE_RE)?;
 
-    let mut nodes = Vec::new();
-    // let mut node_map = NodeMap::new();
-    let mut name_map = HashMap::new();
-    let mut start_id = Vec::new();
-    let mut end_id = Vec::new();
+    let mut definitions = Vec::new();
 
     for (id, line) in reader.lines().enumerate() {
         let line = line?;
-        let captures = node_re.captures(&line);
-
-        if let Some(captures) = captures {
-            let (_, [name, left, right]) = captures.extract();
-
-            if part2 && name.ends_with('A') {
-                start_id.push(id);
-            } else if part2 && name.ends_with('Z') {
-                end_id.push(id);
-            } else if name == "AAA" {
-                start_id.push(id);
-            } else if name == "ZZZ" {
-                end_id.push(id);
+        // Nodes follow the path and an empty line
+        let line_number = id + 3;
+
+        let Some(captures) = node_re.captures(&line) else {
+            return Err(Error::parse(line_number, &line, "Invalid node"));
+        };
+        let (_, [name, left, right]) = captures.extract();
+
+        definitions.push(Definition {
+            line: line_number,
+            name: name.to_owned(),
+            left: left.to_owned(),
+            right: right.to_owned(),
+        });
+    }
+
+    Ok(definitions)
+}
+
+/// Problem found in the node network
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// Node refers to `target`, which is never defined
+    UndefinedTarget {
+        line: usize,
+        node: String,
+        target: String,
+    },
+    /// Node is defined again, its first definition is on `first_line`
+    Duplicate {
+        line: usize,
+        node: String,
+        first_line: usize,
+    },
+    /// Node can't be reached from any start node
+    Unreachable { line: usize, node: String },
+    /// Ghost starting at the node never stands on an end node
+    NeverEnds { line: usize, node: String },
+}
+
+impl Diagnostic {
+    /// Line the problematic node is defined on
+    pub fn line(&self) -> usize {
+        match self {
+            Diagnostic::UndefinedTarget { line, .. }
+            | Diagnostic::Duplicate { line, .. }
+            | Diagnostic::Unreachable { line, .. }
+            | Diagnostic::NeverEnds { line, .. } => *line,
+        }
+    }
+
+    /// Whether the network can't be built at all because of the problem
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Diagnostic::UndefinedTarget { .. } | Diagnostic::Duplicate { .. }
+        )
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::UndefinedTarget { line, node, target } => {
+                write!(
+                    f,
+                    "line {}: {} refers to undefined node {}",
+                    line, node, target
+                )
+            }
+            Diagnostic::Duplicate {
+                line,
+                node,
+                first_line,
+            } => write!(
+                f,
+                "line {}: {} is already defined on line {}",
+                line, node, first_line
+            ),
+            Diagnostic::Unreachable { line, node } => {
+                write!(
+                    f,
+                    "line {}: {} can't be reached from any start node",
+                    line, node
+                )
+            }
+            Diagnostic::NeverEnds { line, node } => write!(
+                f,
+                "line {}: ghost starting at {} never stands on an end node",
+                line, node
+            ),
+        }
+    }
+}
+
+impl From<Diagnostic> for Error {
+    fn from(diagnostic: Diagnostic) -> Self {
+        let line = diagnostic.line();
+        match diagnostic {
+            Diagnostic::UndefinedTarget { target, .. } => {
+                Error::parse(line, &target, "Undefined node")
+            }
+            Diagnostic::Duplicate {
+                node, first_line, ..
+            } => Error::parse(
+                line,
+                &node,
+                format!("Node already defined on line {}", first_line),
+            ),
+            Diagnostic::Unreachable { node, .. } => {
+                Error::parse(line, &node, "Node can't be reached from any start node")
             }
+            Diagnostic::NeverEnds { node, .. } => {
+                Error::parse(line, &node, "Ghost never stands on an end node")
+            }
+        }
+    }
+}
 
-            nodes.push((left.to_owned(), right.to_owned()));
-            // node_map.insert(id, name.to_owned());
-            name_map.insert(name.to_owned(), id);
+/// Resolve node names into ids
+///
+/// Fails with all duplicate definitions and undefined targets, ordered by line.
+fn resolve(
+    definitions: &[Definition],
+    starts: &NodeSet,
+    ends: &NodeSet,
+) -> Result<Network, Vec<Diagnostic>> {
+    let mut diagnostics = Vec::new();
+
+    // First definition of every name
+    let mut name_map = HashMap::new();
+    let mut unique: Vec<&Definition> = Vec::new();
+    for definition in definitions {
+        if let Some(&id) = name_map.get(definition.name.as_str()) {
+            let first: &Definition = unique[id];
+            diagnostics.push(Diagnostic::Duplicate {
+                line: definition.line,
+                node: definition.name.clone(),
+                first_line: first.line,
+            });
         } else {
-            return Err(format!("Invalid input: {}", line).into());
+            name_map.insert(definition.name.as_str(), unique.len());
+            unique.push(definition);
         }
     }
 
+    let mut nodes = Vec::new();
+    for definition in &unique {
+        for target in [&definition.left, &definition.right].into_iter().dedup() {
+            if !name_map.contains_key(target.as_str()) {
+                diagnostics.push(Diagnostic::UndefinedTarget {
+                    line: definition.line,
+                    node: definition.name.clone(),
+                    target: target.clone(),
+                });
+            }
+        }
+        if let (Some(&left), Some(&right)) = (
+            name_map.get(definition.left.as_str()),
+            name_map.get(definition.right.as_str()),
+        ) {
+            nodes.push((left, right));
+        }
+    }
+
+    if !diagnostics.is_empty() {
+        diagnostics.sort_by_key(Diagnostic::line);
+        return Err(diagnostics);
+    }
+
     // println!("{:?}", nodes);
-    // println!("{:?}", node_map);
     // println!("{:?}", name_map);
 
-    let nodes = nodes
-        .into_iter()
-        .map(|(left, right)| (name_map[left.as_str()], name_map[right.as_str()]))
-        .collect();
+    Ok(Network {
+        names: unique.iter().map(|d| d.name.clone()).collect(),
+        lines: unique.iter().map(|d| d.line).collect(),
+        nodes,
+        start_ids: unique
+            .iter()
+            .positions(|d| starts.contains(&d.name))
+            .collect(),
+        end_ids: unique
+            .iter()
+            .positions(|d| ends.contains(&d.name))
+            .collect(),
+    })
+}
+
+/// Parse node network, nodes matching `starts` and `ends` become start and end nodes
+///
+/// Undefined or duplicate nodes are reported as the error of the first such line.
+pub fn parse_nodes<R: std::io::BufRead>(
+    reader: &mut R,
+    starts: &NodeSet,
+    ends: &NodeSet,
+) -> Result<Network, Error> {
+    let definitions = parse_definitions(reader)?;
 
-    Ok((nodes, start_id, end_id))
+    resolve(&definitions, starts, ends)
+        .map_err(|diagnostics| diagnostics.into_iter().next().unwrap().into())
 }
 
-fn find_cycle(nodes: &Nodes, path: &[usize], start_id: usize, end_ids: &[usize]) -> ActorCycle {
-    let mut current = start_id;
-    let mut visited = HashMap::new();
-    let mut step_end_indexes = Vec::new();
-    let mut steps = 0;
+/// Find all problems in the input instead of stopping at the first one
+///
+/// Invalid lines are still returned as errors. Reachability is only checked
+/// once all nodes are defined exactly once.
+pub fn validate(input: &str, starts: &NodeSet, ends: &NodeSet) -> Result<Vec<Diagnostic>, Error> {
+    let Map { path, definitions } = parse_input(input)?;
+    let network = match resolve(&definitions, starts, ends) {
+        Ok(network) => network,
+        Err(diagnostics) => return Ok(diagnostics),
+    };
+
+    let mut diagnostics = Vec::new();
+
+    let reachable = search::flood_fill(network.start_ids.iter().copied(), |&id| {
+        let (left, right) = network.nodes[id];
+        [left, right]
+    });
+    for id in (0..network.nodes.len()).filter(|id| !reachable.contains(id)) {
+        diagnostics.push(Diagnostic::Unreachable {
+            line: network.lines[id],
+            node: network.names[id].clone(),
+        });
+    }
 
-    loop {
-        for (i, &direction) in path.iter().enumerate() {
-            if end_ids.contains(&current) {
-                step_end_indexes.push(steps);
-            }
+    for (&start_id, actor) in network.start_ids.iter().zip(find_cycles(&path, &network)) {
+        if actor.prefix_end_indexes.is_empty() && actor.loop_end_indexes.is_empty() {
+            diagnostics.push(Diagnostic::NeverEnds {
+                line: network.lines[start_id],
+                node: network.names[start_id].clone(),
+            });
+        }
+    }
 
-            if visited.contains_key(&(i, current)) {
-                let offset = visited[&(i, current)];
-                let length = steps - offset;
-                return ActorCycle {
-                    offset,
-                    length,
-                    prefix_end_indexes: step_end_indexes
-                        .iter()
-                        .filter(|&&x| x < offset)
-                        .copied()
-                        .collect(),
-                    loop_end_indexes: step_end_indexes
-                        .iter()
-                        .filter(|&&x| x >= offset)
-                        .map(|&x| x - offset)
-                        .collect(),
-                };
-            }
+    diagnostics.sort_by_key(Diagnostic::line);
+    Ok(diagnostics)
+}
+
+/// Next state after following a single instruction
+///
+/// Actor without instructions stays where it is.
+pub fn step(nodes: &Nodes, path: &[usize], (i, current): State) -> State {
+    let Some(&direction) = path.get(i) else {
+        return (i, current);
+    };
+    let (left, right) = nodes[current];
+    let next = if direction == 0 { left } else { right };
+    ((i + 1) % path.len(), next)
+}
 
-            visited.insert((i, current.to_owned()), steps);
-            steps += 1;
+/// Cycle of every start node
+pub fn find_cycles(path: &[usize], network: &Network) -> Vec<ActorCycle> {
+    network
+        .start_ids
+        .iter()
+        .map(|&start_id| find_cycle(&network.nodes, path, start_id, &network.end_ids))
+        .collect_vec()
+}
 
-            let (left, right) = nodes[current];
+/// Follow path from `start_id` until a (instruction, node) state repeats
+pub fn find_cycle(nodes: &Nodes, path: &[usize], start_id: usize, end_ids: &[usize]) -> ActorCycle {
+    let step = |&state: &State| step(nodes, path, state);
 
-            if direction == 0 {
-                current = left;
-            } else {
-                current = right;
-            }
+    let cycle = cycle::find_cycle(&(0, start_id), step);
+    let offset = cycle.start as u64;
+    let length = cycle.length as u64;
+
+    // Walk again through the prefix and a single loop to collect visits of end nodes
+    let mut step_end_indexes = Vec::new();
+    let mut state = (0, start_id);
+    for steps in 0..offset + length {
+        if end_ids.contains(&state.1) {
+            step_end_indexes.push(steps);
         }
+        state = step(&state);
+    }
+
+    ActorCycle {
+        offset,
+        length,
+        prefix_end_indexes: step_end_indexes
+            .iter()
+            .filter(|&&x| x < offset)
+            .copied()
+            .collect(),
+        loop_end_indexes: step_end_indexes
+            .iter()
+            .filter(|&&x| x >= offset)
+            .map(|&x| x - offset)
+            .collect(),
     }
 }
 
-fn solve(cycles: &[ActorCycle]) -> Option<u64> {
-    let mut stack = Vec::new();
-    let mut solutions = Vec::new();
+/// Steps walked before solving the ghost loops arithmetically
+const SIMULATED_STEPS: u64 = 10_000;
 
+/// First step at which all ghosts stand on an end node
+///
+/// `None` if they never do, or not within `u64::MAX` steps.
+pub fn solve(cycles: &[ActorCycle]) -> Option<u64> {
     // println!("{:?}", cycles);
 
-    solve_rec(cycles, &mut stack, &mut solutions);
+    // Ghosts meet either before one of them enters its loop,
+    // on one of the end nodes that ghost visits only once
+    let prefix_meeting = cycles
+        .iter()
+        .flat_map(|cycle| &cycle.prefix_end_indexes)
+        .copied()
+        .filter(|&step| cycles.iter().all(|cycle| cycle.visits_end(step)))
+        .min();
+    if prefix_meeting.is_some() {
+        // Loop meetings come only after every ghost left its prefix
+        return prefix_meeting;
+    }
 
-    solutions.into_iter().min()
-}
+    // or once all of them walk in their loops
+    let start = cycles.iter().map(|cycle| cycle.offset).max()?;
 
-fn solve_rec(cycles: &[ActorCycle], stack: &mut Vec<(u64, Option<u64>)>, solutions: &mut Vec<u64>) {
-    if cycles.is_empty() {
-        // Check if current combination is a valid solution
+    // Inputs with many end nodes tend to meet soon, walking a few steps is cheaper then
+    let simulated = start..start.saturating_add(SIMULATED_STEPS);
+    if let Some(step) = simulated
+        .clone()
+        .find(|&step| cycles.iter().all(|cycle| cycle.visits_end(step)))
+    {
+        return Some(step);
+    }
 
-        // println!("{:?}", stack);
+    // Ghosts standing on end nodes all the time don't constrain anything,
+    // the rest are combined starting with the fewest end nodes to keep few residues around
+    let constraining = cycles
+        .iter()
+        .filter(|cycle| (cycle.loop_end_indexes.len() as u64) < cycle.length)
+        .sorted_by_key(|cycle| cycle.loop_end_indexes.len())
+        .collect_vec();
+    let (residues, modulus) = loop_residues(&constraining);
+    let start = simulated.end as i128;
+    residues
+        .into_iter()
+        .filter_map(|residue| u64::try_from(start + (residue - start).rem_euclid(modulus)).ok())
+        .min()
+}
 
-        if stack.is_empty() {
-            return;
+/// Steps modulo the lcm of loop lengths at which all looping ghosts stand on an end node
+///
+/// Ghosts are added one at a time, so the number of residues kept never exceeds the lcm
+/// instead of growing with every combination of end nodes.
+fn loop_residues(cycles: &[&ActorCycle]) -> (Vec<i128>, i128) {
+    let mut residues = vec![0];
+    let mut modulus = 1;
+
+    for cycle in cycles {
+        let length = cycle.length as i128;
+        // Every successful combination has the same modulus, the lcm of lengths so far
+        let mut combined_modulus = modulus;
+        let mut combined = Vec::new();
+        for &residue in &residues {
+            for &loop_end_index in &cycle.loop_end_indexes {
+                let end = (cycle.offset + loop_end_index) as i128 % length;
+                if let Some((r, m)) = crt((residue, modulus), (end, length)) {
+                    combined.push(r);
+                    combined_modulus = m;
+                }
+            }
         }
+        combined.sort_unstable();
+        combined.dedup();
 
-        if stack.len() == 1 {
-            // Edge case - only one actor (all part1 solutions)
-            let (offset, _) = stack[0];
-            solutions.push(offset);
-            return;
+        residues = combined;
+        modulus = combined_modulus;
+        if residues.is_empty() {
+            break;
         }
+    }
 
-        let mut period = 1;
+    (residues, modulus)
+}
 
-        for actor in stack.iter() {
-            match actor {
-                (_, None) => {
-                    // FIXME: Let's ignore support for non-periodic visits
-                    // This isn't needed to complete the puzzle
-                    return;
-                }
-                (offset, Some(length)) => {
-                    if *offset == *length {
-                        // Edge case used in this puzzle (part2 solution of user specific input)
-                        // Greatly simplifies the solution
-                        // All actors start their cycle at 0 making it easy to find where the period starts and ends
-                        period = num_integer::lcm(period, *length);
-                    } else {
-                        // FIXME: No reason to support this - not used in this puzzle
-                        // This represents inputs where we don't know where the period starts
-                        // I'll happily ignore support for this for now
-                        return;
-                    }
+/// Combine `x = r1 (mod m1)` and `x = r2 (mod m2)` into a single congruence
+///
+/// Moduli don't need to be coprime. `None` if no `x` satisfies both,
+/// or if the combined modulus doesn't fit into `i128`.
+pub fn crt((r1, m1): (i128, i128), (r2, m2): (i128, i128)) -> Option<(i128, i128)> {
+    let ExtendedGcd { gcd, x, .. } = m1.extended_gcd(&m2);
+    let diff = r2 - r1;
+    if diff % gcd != 0 {
+        return None;
+    }
+
+    // `x` is the inverse of `m1 / gcd` modulo `m2 / gcd`
+    let m2 = m2 / gcd;
+    let k = (diff / gcd).rem_euclid(m2).checked_mul(x.rem_euclid(m2))? % m2;
+    let modulus = m1.checked_mul(m2)?;
+
+    Some(((r1 + m1 * k).rem_euclid(modulus), modulus))
+}
+
+/// Graphviz DOT graph of the network
+///
+/// Start nodes are drawn as boxes and end nodes as double circles.
+/// Edges taken by the ghosts in their loops are highlighted, a colour per ghost.
+pub fn to_dot(path: &[usize], network: &Network, cycles: &[ActorCycle]) -> String {
+    const COLORS: [&str; 6] = ["red", "blue", "green3", "orange", "purple", "cyan3"];
+
+    // Colours of every (node, direction) edge and node walked in a loop
+    let mut edge_colors: HashMap<(usize, usize), Vec<&str>> = HashMap::new();
+    let mut node_colors: HashMap<usize, &str> = HashMap::new();
+    for ((&start_id, actor), color) in network
+        .start_ids
+        .iter()
+        .zip(cycles)
+        .zip(COLORS.iter().cycle())
+    {
+        let step = |&state: &State| step(&network.nodes, path, state);
+        let mut state = cycle::nth(&(0, start_id), step, actor.offset as usize);
+        for _ in 0..actor.length {
+            let (i, current) = state;
+            if let Some(&direction) = path.get(i) {
+                let colors = edge_colors.entry((current, direction)).or_default();
+                if !colors.contains(color) {
+                    colors.push(color);
                 }
             }
+            node_colors.entry(current).or_insert(color);
+            state = step(&state);
         }
-
-        solutions.push(period);
-
-        // println!("{} - {:?}", period, stack);
-        // println!("{:?}", stack);
-        return;
     }
 
-    let current = &cycles[0];
-    let others = &cycles[1..];
-
-    for &prefix_end_index in &current.prefix_end_indexes {
-        stack.push((prefix_end_index, None));
-        solve_rec(others, stack, solutions);
-        stack.pop();
+    let quote = |id: usize| {
+        format!(
+            "\"{}\"",
+            network.names[id].replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    };
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph network {{").unwrap();
+    for id in 0..network.nodes.len() {
+        let mut attributes = Vec::new();
+        if network.start_ids.contains(&id) {
+            attributes.push("shape=box".to_owned());
+        } else if network.end_ids.contains(&id) {
+            attributes.push("shape=doublecircle".to_owned());
+        }
+        if let Some(color) = node_colors.get(&id) {
+            attributes.push(format!("color={}", color));
+        }
+        if attributes.is_empty() {
+            writeln!(dot, "    {};", quote(id)).unwrap();
+        } else {
+            writeln!(dot, "    {} [{}];", quote(id), attributes.join(", ")).unwrap();
+        }
     }
-
-    for &loop_end_index in &current.loop_end_indexes {
-        let offset = current.offset + loop_end_index;
-        stack.push((offset, Some(current.length)));
-        solve_rec(others, stack, solutions);
-        stack.pop();
+    for (id, &(left, right)) in network.nodes.iter().enumerate() {
+        for (direction, target, label) in [(0, left, "L"), (1, right, "R")] {
+            let mut attributes = format!("label={}", label);
+            if let Some(colors) = edge_colors.get(&(id, direction)) {
+                write!(attributes, ", color=\"{}\", penwidth=2", colors.join(":")).unwrap();
+            }
+            writeln!(
+                dot,
+                "    {} -> {} [{}];",
+                quote(id),
+                quote(target),
+                attributes
+            )
+            .unwrap();
+        }
     }
+    writeln!(dot, "}}").unwrap();
+
+    dot
 }
@@ -2,63 +2,53 @@ use itertools::Itertools;
 use regex::Regex;
 use std::io::BufRead;
 
-use crate::utils;
+use crate::error::{parse_number, Error};
+use crate::solution::{Part, Solution};
 
 const NUM_RE: &str = r"(-?[0-9]+)";
 
-pub fn day09() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day09")? {
-        handle(run(&file));
-    }
-    Ok(())
-}
-
-fn handle(result: Result<(i64, i64), Box<dyn std::error::Error>>) {
-    match result {
-        Ok((result_p1, result_p2)) => println!("Result: Part1 {} | Part2 {}", result_p1, result_p2),
-        Err(e) => println!("Error: {}", e),
-    }
-    println!();
-}
+/// Day 9: Mirage Maintenance
+pub struct Day09;
 
-fn run(file: &str) -> Result<(i64, i64), Box<dyn std::error::Error>> {
-    println!("DAY09: {}", file);
+impl Solution for Day09 {
+    const DAY: u8 = 9;
 
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
+    type Input = Vec<Vec<i64>>;
+    type Answer = i64;
 
-    let input = parse_input(&mut reader)?;
-
-    // println!("{:?}", input);
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(&mut input.as_bytes())
+    }
 
-    let results = input
-        .into_iter()
-        .map(|line| solve_line(&line))
-        .collect::<Option<Vec<(i64, i64)>>>()
-        .ok_or("No solution")?;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        let results = solve_lines(input).ok_or(Error::no_solution(9, Part::Part1))?;
+        Ok(results.iter().map(|(_, next)| next).sum())
+    }
 
-    let (results1, results2): (Vec<i64>, Vec<i64>) = results.into_iter().unzip();
-    // .sum::<Option<i64>>()
-    // .ok_or("No solution")?;
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        let results = solve_lines(input).ok_or(Error::no_solution(9, Part::Part2))?;
+        Ok(results.iter().map(|(prev, _)| prev).sum())
+    }
+}
 
-    Ok((results1.iter().sum(), results2.iter().sum()))
+fn solve_lines(input: &[Vec<i64>]) -> Option<Vec<(i64, i64)>> {
+    input.iter().map(|line| solve_line(line)).collect()
 }
 
-fn parse_input<R: std::io::BufRead>(
-    reader: &mut R,
-) -> Result<Vec<Vec<i64>>, Box<dyn std::error::Error>> {
+/// Parse sequences of numbers
+pub fn parse_input<R: std::io::BufRead>(reader: &mut R) -> Result<Vec<Vec<i64>>, Error> {
     let num_re = Regex::new(NUM_RE)?;
 
     let mut lines = Vec::new();
 
-    for line in reader.lines() {
+    for (i, line) in reader.lines().enumerate() {
         let line = line?;
 
         let mut nums = Vec::new();
 
         for cap in num_re.captures_iter(line.as_str()) {
             let (_, [num]) = cap.extract();
-            nums.push(num.parse::<i64>()?);
+            nums.push(parse_number::<i64>(i + 1, &line, num)?);
         }
         lines.push(nums);
     }
@@ -66,7 +56,8 @@ fn parse_input<R: std::io::BufRead>(
     Ok(lines)
 }
 
-fn solve_line(line: &[i64]) -> Option<(i64, i64)> {
+/// Extrapolate previous and next value of the sequence
+pub fn solve_line(line: &[i64]) -> Option<(i64, i64)> {
     // println!("{:?}", line);
 
     if line.is_empty() {
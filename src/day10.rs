@@ -1,30 +1,39 @@
-use std::{collections::HashMap, collections::HashSet, io::BufRead};
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+use std::str::FromStr;
 
-use crate::utils;
+use crate::error::{Error, ParseError};
+use crate::solution::{Part, Solution};
+use crate::utils::grid::{Dir, Grid, Pos, DIRECTIONS4};
+use crate::utils::search;
 
-type PipeShape = char;
+pub type PipeShape = char;
 
+/// Shapes of pipes, each connecting two directions
+const PIPE_SHAPES: [PipeShape; 6] = ['|', '-', 'L', 'J', '7', 'F'];
+
+/// Pipe tiles and position of the start tile
+///
+/// The start tile holds its real pipe shape instead of `S`.
 #[derive(Debug)]
-struct PipeMap {
-    map: Vec<Vec<PipeShape>>,
-    start: (usize, usize),
+pub struct PipeMap {
+    pub map: Grid<PipeShape>,
+    pub start: Pos,
 }
 
-const ALL_DIRECTIONS: [(i64, i64); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-
-fn dir_invert(dir: (i64, i64)) -> (i64, i64) {
+fn dir_invert(dir: Dir) -> Dir {
     (-dir.0, -dir.1)
 }
 
-// fn dir_rotate_right(dir: (i64, i64)) -> (i64, i64) {
+// fn dir_rotate_right(dir: Dir) -> Dir {
 //     (dir.1, -dir.0)
 // }
 
-// fn dir_rotate_left(dir: (i64, i64)) -> (i64, i64) {
+// fn dir_rotate_left(dir: Dir) -> Dir {
 //     (-dir.1, dir.0)
 // }
 
-fn shape_connects_to(shape: PipeShape, direction: (i64, i64)) -> bool {
+fn shape_connects_to(shape: PipeShape, direction: Dir) -> bool {
     matches!(
         (shape, direction),
         ('|', (0, -1))
@@ -39,15 +48,14 @@ fn shape_connects_to(shape: PipeShape, direction: (i64, i64)) -> bool {
             | ('7', (-1, 0))
             | ('F', (0, 1))
             | ('F', (1, 0))
-            | ('S', _)
     )
 }
 
-fn shapes_are_connected(shape1: PipeShape, shape2: PipeShape, direction: (i64, i64)) -> bool {
+fn shapes_are_connected(shape1: PipeShape, shape2: PipeShape, direction: Dir) -> bool {
     shape_connects_to(shape1, direction) && shape_connects_to(shape2, dir_invert(direction))
 }
 
-fn shape_neighbors_right(shape: PipeShape, dir: (i64, i64)) -> Vec<(i64, i64)> {
+fn shape_neighbors_right(shape: PipeShape, dir: Dir) -> Vec<Dir> {
     match (shape, dir) {
         ('|', (0, 1)) => vec![(-1, 0)],
         ('|', (0, -1)) => vec![(1, 0)],
@@ -63,15 +71,11 @@ fn shape_neighbors_right(shape: PipeShape, dir: (i64, i64)) -> Vec<(i64, i64)> {
         ('7', (-1, 0)) => vec![(0, -1), (1, 0)],
         ('F', (0, 1)) => vec![(0, -1), (-1, 0)],
         ('F', (1, 0)) => vec![],
-        ('S', _) => vec![],
-        _ => {
-            println!("{} {:?}", shape, dir);
-            vec![]
-        }
+        _ => vec![],
     }
 }
 
-fn shape_neighbors_left(shape: PipeShape, dir: (i64, i64)) -> Vec<(i64, i64)> {
+fn shape_neighbors_left(shape: PipeShape, dir: Dir) -> Vec<Dir> {
     match (shape, dir) {
         ('|', (0, 1)) => vec![(1, 0)],
         ('|', (0, -1)) => vec![(-1, 0)],
@@ -87,13 +91,12 @@ fn shape_neighbors_left(shape: PipeShape, dir: (i64, i64)) -> Vec<(i64, i64)> {
         ('7', (-1, 0)) => vec![],
         ('F', (0, 1)) => vec![],
         ('F', (1, 0)) => vec![(0, -1), (-1, 0)],
-        ('S', _) => vec![],
         _ => vec![],
     }
 }
 
-// fn print_map(map: &PipeMap, highlight1: &HashSet<(usize, usize)>, highlight2: &HashSet<(usize, usize)>, directions: &HashMap<(usize, usize), (i64, i64)>) {
-//     for (y, row) in map.map.iter().enumerate() {
+// fn print_map(map: &PipeMap, highlight1: &HashSet<Pos>, highlight2: &HashSet<Pos>, directions: &HashMap<Pos, Dir>) {
+//     for (y, row) in map.map.rows().enumerate() {
 //         for (x, shape) in row.iter().enumerate() {
 //             let symbol = match directions.get(&(x, y)) {
 //                 Some((0, -1)) => '^',
@@ -117,160 +120,195 @@ fn shape_neighbors_left(shape: PipeShape, dir: (i64, i64)) -> Vec<(i64, i64)> {
 //     println!();
 // }
 
-pub fn day10() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day10")? {
-        handle(run(&file, false));
-        handle(run(&file, true));
-    }
-    Ok(())
+/// Way of counting tiles enclosed by the loop
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AreaMethod {
+    /// Flood fill both sides of the loop, the one not reaching the map border is inside
+    #[default]
+    FloodFill,
+    /// Area of the loop polygon by the shoelace formula, tiles inside it by Pick's theorem
+    Shoelace,
 }
 
-fn handle(result: Result<u64, Box<dyn std::error::Error>>) {
-    match result {
-        Ok(result) => println!("Result: {}", result),
-        Err(e) => println!("Error: {}", e),
+impl FromStr for AreaMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flood" => Ok(AreaMethod::FloodFill),
+            "shoelace" => Ok(AreaMethod::Shoelace),
+            _ => Err(format!(
+                "Invalid area method: {} (expected flood or shoelace)",
+                s
+            )),
+        }
     }
-    println!();
 }
 
-fn run(file: &str, part2: bool) -> Result<u64, Box<dyn std::error::Error>> {
-    println!("DAY10: Part{}: {}", if part2 { "2" } else { "1" }, file);
-
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
+impl Display for AreaMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AreaMethod::FloodFill => write!(f, "Flood fill"),
+            AreaMethod::Shoelace => write!(f, "Shoelace formula"),
+        }
+    }
+}
 
-    let map = parse_pipe_map(&mut reader)?;
-    // println!("{:?}", map);
+/// Day 10: Pipe Maze
+pub struct Day10;
 
-    let solver = if part2 { solve_p2 } else { solve_p1 };
-    let result = solver(&map).ok_or("No solution")?;
+impl Solution for Day10 {
+    const DAY: u8 = 10;
 
-    Ok(result)
-}
+    type Input = PipeMap;
+    type Answer = u64;
 
-fn parse_pipe_map<R: std::io::BufRead>(
-    reader: &mut R,
-) -> Result<PipeMap, Box<dyn std::error::Error>> {
-    let mut map = Vec::new();
-    let mut start = (0, 0);
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_pipe_map(input)
+    }
 
-    for (y, line) in reader.lines().enumerate() {
-        let line = line?;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve_p1(input).ok_or(Error::no_solution(10, Part::Part1))
+    }
 
-        let mut row = Vec::new();
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        // Flood fill can't tell inside from outside if the loop leaves no tiles on one side
+        solve_p2(input)
+            .or_else(|| solve_p2_shoelace(input))
+            .ok_or(Error::no_solution(10, Part::Part2))
+    }
 
-        for (x, c) in line.chars().enumerate() {
-            if c == 'S' {
-                start = (x, y);
-            }
-            row.push(c);
+    fn warnings(&self, input: &Self::Input, part: Part) -> Vec<String> {
+        if part == Part::Part2 && solve_p2(input).is_none() {
+            vec!["Flood fill found no answer, used the shoelace formula instead".to_owned()]
+        } else {
+            Vec::new()
         }
-
-        map.push(row);
     }
+}
+
+/// Parse pipe map and infer the shape of the start tile
+pub fn parse_pipe_map(input: &str) -> Result<PipeMap, Error> {
+    let mut map = Grid::parse(input, Some)?;
+    let start = map.find(|&c| c == 'S').ok_or_else(|| {
+        Error::parse(
+            1,
+            input.lines().next().unwrap_or_default(),
+            "Missing start tile",
+        )
+    })?;
+
+    // Start tile must be part of exactly one loop
+    let shapes: Vec<PipeShape> = PIPE_SHAPES
+        .iter()
+        .copied()
+        .filter(|&shape| {
+            map[start] = shape;
+            follow_loop(&map, start).is_some()
+        })
+        .collect();
+
+    let shape = match shapes[..] {
+        [shape] => shape,
+        _ => {
+            let (x, y) = start;
+            let line = input.lines().nth(y).unwrap_or_default();
+            let text = line
+                .char_indices()
+                .nth(x)
+                .map_or("", |(i, c)| &line[i..i + c.len_utf8()]);
+            let message = if shapes.is_empty() {
+                "Start tile is not part of any loop".to_owned()
+            } else {
+                format!("Start tile is part of {} possible loops", shapes.len())
+            };
+            return Err(ParseError::within(y + 1, line, text, message).into());
+        }
+    };
+    map[start] = shape;
 
     Ok(PipeMap { map, start })
 }
 
-fn solve_p1(map: &PipeMap) -> Option<u64> {
-    // Dijkstra algorithm using HashMap instead of 2D array to keep track of visited nodes
-    // for no reason at all
-    // Also queue is not a priority queue, but it doesn't change much as there are only 2 paths to follow
+/// Tiles of the loop in order, starting with the start tile
+pub fn find_loop(map: &PipeMap) -> Option<Vec<Pos>> {
+    follow_loop(&map.map, map.start)
+}
 
-    // Assume map is rectangular
-    let width = map.map[0].len();
-    let height = map.map.len();
+/// Tiles of the loop going through `start`, starting with it
+///
+/// `None` if following pipes from `start` doesn't lead back to it.
+fn follow_loop(map: &Grid<PipeShape>, start: Pos) -> Option<Vec<Pos>> {
+    let mut dir = *DIRECTIONS4
+        .iter()
+        .find(|&&dir| shape_connects_to(map[start], dir))?;
+    let mut pos = start;
+    let mut tiles = vec![start];
+
+    loop {
+        let next = map.offset(pos, dir)?;
+        if !shapes_are_connected(map[pos], map[next], dir) {
+            return None;
+        }
+        if next == start {
+            return Some(tiles);
+        }
 
-    let mut visited = HashMap::new();
-    let mut queue = Vec::new();
+        // Every pipe connects two directions, continue with the one we didn't come from
+        // The path can't be reversed, so the first tile visited twice must be the start
+        let back = dir_invert(dir);
+        dir = *DIRECTIONS4
+            .iter()
+            .find(|&&d| d != back && shape_connects_to(map[next], d))?;
+        pos = next;
+        tiles.push(pos);
+    }
+}
 
-    visited.insert(map.start, 0);
-    queue.push((map.start, 0));
-
-    while let Some((pos, steps)) = queue.pop() {
-        let (x, y) = pos;
-        let shape = map.map[y][x];
-
-        for dir in &ALL_DIRECTIONS {
-            let (dx, dy) = dir;
-            let new_pos = (x as i64 + dx, y as i64 + dy);
-            if new_pos.0 < 0
-                || new_pos.1 < 0
-                || new_pos.0 >= width as i64
-                || new_pos.1 >= height as i64
-            {
-                continue;
-            }
-            let new_pos = (new_pos.0 as usize, new_pos.1 as usize);
-            if visited.contains_key(&new_pos) && visited[&new_pos] <= steps + 1 {
-                continue;
-            }
+/// Tiles next to `pos` whose pipes connect with the pipe at `pos`
+fn connected_neighbours(map: &PipeMap, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+    let shape = map.map[pos];
+    DIRECTIONS4.iter().filter_map(move |&dir| {
+        let new_pos = map.map.offset(pos, dir)?;
+        shapes_are_connected(shape, map.map[new_pos], dir).then_some(new_pos)
+    })
+}
 
-            if let Some(new_shape) = map.map.get(new_pos.1).and_then(|row| row.get(new_pos.0)) {
-                if shapes_are_connected(shape, *new_shape, *dir) {
-                    visited.insert(new_pos, steps + 1);
-                    queue.push((new_pos, steps + 1));
-                }
-            }
-        }
-    }
+/// Distance to the farthest tile of the loop
+pub fn solve_p1(map: &PipeMap) -> Option<u64> {
+    let distances = search::bfs([map.start], |&pos| connected_neighbours(map, pos));
 
-    // println!("{:?}", visited);
+    // println!("{:?}", distances.costs);
 
-    visited.values().max().copied()
+    distances.costs.values().max().map(|&steps| steps as u64)
 }
 
-fn flood_fill(
-    map: &PipeMap,
-    edges: &HashSet<(usize, usize)>,
-    queue: &mut Vec<(usize, usize)>,
-) -> (HashSet<(usize, usize)>, bool) {
+fn flood_fill(map: &PipeMap, edges: &HashSet<Pos>, queue: &[Pos]) -> (HashSet<Pos>, bool) {
     // Flood fill limited by set of edge pipes
 
-    // Assume map is rectangular
-    let width = map.map[0].len();
-    let height = map.map.len();
+    let starts = queue.iter().copied().filter(|pos| !edges.contains(pos));
+    let visited = search::flood_fill(starts, |&pos| {
+        map.map
+            .neighbours4(pos)
+            .filter(|new_pos| !edges.contains(new_pos))
+            .collect::<Vec<_>>()
+    });
 
-    let mut overflow = false;
-    let mut visited = HashSet::new();
-
-    while let Some(pos) = queue.pop() {
-        if edges.contains(&pos) || visited.contains(&pos) {
-            continue;
-        }
-        visited.insert(pos);
-
-        let (x, y) = pos;
-
-        for dir in &ALL_DIRECTIONS {
-            let (dx, dy) = dir;
-            let new_pos = (x as i64 + dx, y as i64 + dy);
-            if new_pos.0 < 0
-                || new_pos.1 < 0
-                || new_pos.0 >= width as i64
-                || new_pos.1 >= height as i64
-            {
-                overflow = true;
-                continue;
-            }
-            let new_pos = (new_pos.0 as usize, new_pos.1 as usize);
-            queue.push(new_pos);
-        }
-    }
+    // Fill overflows the map if it reaches its border
+    let overflow = visited
+        .iter()
+        .any(|&(x, y)| x == 0 || y == 0 || x == map.map.width() - 1 || y == map.map.height() - 1);
 
     (visited, overflow)
 }
 
-fn solve_p2(map: &PipeMap) -> Option<u64> {
+/// Number of tiles enclosed by the loop
+pub fn solve_p2(map: &PipeMap) -> Option<u64> {
     // Deformed Dijkstra algorithm to only follow one path
     // this way we can use the direction vector to know which side of the loop is inside and which is outside
     // Actually we only know which side is right/left side of the loop
     // when flood filling we can check which side is outside by checking if the flood fill reaches the edge of the map
 
-    // Assume map is rectangular
-    let width = map.map[0].len();
-    let height = map.map.len();
-
     // let mut directions = HashMap::new();
     let mut visited = HashSet::new();
     let mut queue = Vec::new();
@@ -284,67 +322,64 @@ fn solve_p2(map: &PipeMap) -> Option<u64> {
 
     visited.insert(map.start);
     queue.push(map.start);
+    let mut last = map.start;
 
     while let Some(pos) = queue.pop() {
-        let (x, y) = pos;
-        let shape = map.map[y][x];
-
-        for dir in &ALL_DIRECTIONS {
-            let (dx, dy) = dir;
-            let new_pos = (x as i64 + dx, y as i64 + dy);
-            if new_pos.0 < 0
-                || new_pos.1 < 0
-                || new_pos.0 >= width as i64
-                || new_pos.1 >= height as i64
-            {
+        let shape = map.map[pos];
+
+        for dir in &DIRECTIONS4 {
+            let Some(new_pos) = map.map.offset(pos, *dir) else {
                 continue;
-            }
-            let new_pos = (new_pos.0 as usize, new_pos.1 as usize);
-            let (nx, ny) = new_pos;
+            };
 
             if visited.contains(&new_pos) {
                 continue;
             }
 
-            if let Some(new_shape) = map.map.get(new_pos.1).and_then(|row| row.get(new_pos.0)) {
-                if shapes_are_connected(shape, *new_shape, *dir) {
-                    // directions.insert(pos, *dir);
-
-                    visited.insert(new_pos);
-                    queue.push(new_pos);
-
-                    for fill_dir in shape_neighbors_left(*new_shape, dir_invert(*dir)) {
-                        let fill_pos = (nx as i64 + fill_dir.0, ny as i64 + fill_dir.1);
-                        if !(fill_pos.0 < 0
-                            || fill_pos.1 < 0
-                            || fill_pos.0 >= width as i64
-                            || fill_pos.1 >= height as i64)
-                        {
-                            let fill_pos = (fill_pos.0 as usize, fill_pos.1 as usize);
-                            fill_queue_right.push(fill_pos);
-                        }
-                    }
+            let new_shape = map.map[new_pos];
+            if shapes_are_connected(shape, new_shape, *dir) {
+                // directions.insert(pos, *dir);
+
+                visited.insert(new_pos);
+                queue.push(new_pos);
+                last = new_pos;
 
-                    for fill_dir in shape_neighbors_right(*new_shape, dir_invert(*dir)) {
-                        let fill_pos = (nx as i64 + fill_dir.0, ny as i64 + fill_dir.1);
-                        if !(fill_pos.0 < 0
-                            || fill_pos.1 < 0
-                            || fill_pos.0 >= width as i64
-                            || fill_pos.1 >= height as i64)
-                        {
-                            let fill_pos = (fill_pos.0 as usize, fill_pos.1 as usize);
-                            fill_queue_left.push(fill_pos);
-                        }
+                for fill_dir in shape_neighbors_left(new_shape, dir_invert(*dir)) {
+                    if let Some(fill_pos) = map.map.offset(new_pos, fill_dir) {
+                        fill_queue_right.push(fill_pos);
                     }
+                }
 
-                    // Only follow one direction
-                    // Required to know which side of the loop is inside and which is outside
-                    break;
+                for fill_dir in shape_neighbors_right(new_shape, dir_invert(*dir)) {
+                    if let Some(fill_pos) = map.map.offset(new_pos, fill_dir) {
+                        fill_queue_left.push(fill_pos);
+                    }
                 }
+
+                // Only follow one direction
+                // Required to know which side of the loop is inside and which is outside
+                break;
             }
         }
     }
 
+    // Sides of the start tile, entered from the last tile of the loop
+    let dir = (
+        map.start.0 as isize - last.0 as isize,
+        map.start.1 as isize - last.1 as isize,
+    );
+    let shape = map.map[map.start];
+    for fill_dir in shape_neighbors_left(shape, dir_invert(dir)) {
+        if let Some(fill_pos) = map.map.offset(map.start, fill_dir) {
+            fill_queue_right.push(fill_pos);
+        }
+    }
+    for fill_dir in shape_neighbors_right(shape, dir_invert(dir)) {
+        if let Some(fill_pos) = map.map.offset(map.start, fill_dir) {
+            fill_queue_left.push(fill_pos);
+        }
+    }
+
     // Debug
     // let mut fill_overflow_right = false;
     // let mut fill_visited_right = HashSet::new();
@@ -353,9 +388,8 @@ fn solve_p2(map: &PipeMap) -> Option<u64> {
     // fill_visited_right.extend(fill_queue_right);
     // fill_visited_left.extend(fill_queue_left);
 
-    let (fill_visited_right, fill_overflow_right) =
-        flood_fill(map, &visited, &mut fill_queue_right);
-    let (fill_visited_left, fill_overflow_left) = flood_fill(map, &visited, &mut fill_queue_left);
+    let (fill_visited_right, fill_overflow_right) = flood_fill(map, &visited, &fill_queue_right);
+    let (fill_visited_left, fill_overflow_left) = flood_fill(map, &visited, &fill_queue_left);
 
     // print_map(map, &visited, &fill_visited_right, &directions);
     // print_map(map, &visited, &fill_visited_left, &directions);
@@ -383,3 +417,40 @@ fn solve_p2(map: &PipeMap) -> Option<u64> {
         }
     }
 }
+
+/// Number of tiles enclosed by the loop, counted using `method`
+pub fn enclosed_tiles(map: &PipeMap, method: AreaMethod) -> Option<u64> {
+    match method {
+        AreaMethod::FloodFill => solve_p2(map),
+        AreaMethod::Shoelace => solve_p2_shoelace(map),
+    }
+}
+
+/// Corners of the loop, the vertices of its polygon
+pub fn loop_vertices(map: &PipeMap, tiles: &[Pos]) -> Vec<Pos> {
+    tiles
+        .iter()
+        .copied()
+        .filter(|&pos| matches!(map.map[pos], 'L' | 'J' | '7' | 'F'))
+        .collect()
+}
+
+/// Area of the polygon with the given vertices in order, doubled to stay an integer
+pub fn shoelace_area2(vertices: &[Pos]) -> u64 {
+    let sum: i64 = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&(x1, y1), &(x2, y2))| x1 as i64 * y2 as i64 - x2 as i64 * y1 as i64)
+        .sum();
+    sum.unsigned_abs()
+}
+
+/// Number of tiles enclosed by the loop, using the shoelace formula and Pick's theorem
+pub fn solve_p2_shoelace(map: &PipeMap) -> Option<u64> {
+    let tiles = find_loop(map)?;
+    let area2 = shoelace_area2(&loop_vertices(map, &tiles));
+
+    // Pick's theorem: A = I + B / 2 - 1, every loop tile is a boundary point
+    let boundary = tiles.len() as u64;
+    (area2 + 2).checked_sub(boundary).map(|inside2| inside2 / 2)
+}
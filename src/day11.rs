@@ -1,75 +1,60 @@
-use std::{collections::HashSet, io::BufRead};
+use std::collections::HashSet;
 
-use crate::utils;
+use crate::error::Error;
+use crate::solution::{Part, Solution};
+use crate::utils::Grid;
 
-struct MapInfo {
-    map: Vec<(usize, usize)>,
-    empty_x: HashSet<usize>,
-    empty_y: HashSet<usize>,
+/// Galaxy positions and empty rows and columns
+pub struct MapInfo {
+    pub map: Vec<(usize, usize)>,
+    pub empty_x: HashSet<usize>,
+    pub empty_y: HashSet<usize>,
 }
 
-pub fn day11() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day11")? {
-        handle(run(&file, false));
-        handle(run(&file, true));
-    }
-    Ok(())
-}
+/// Day 11: Cosmic Expansion
+pub struct Day11;
 
-fn handle(result: Result<usize, Box<dyn std::error::Error>>) {
-    match result {
-        Ok(result) => println!("Result: {}", result),
-        Err(e) => println!("Error: {}", e),
-    }
-    println!();
-}
+impl Solution for Day11 {
+    const DAY: u8 = 11;
 
-fn run(file: &str, part2: bool) -> Result<usize, Box<dyn std::error::Error>> {
-    println!("DAY11: Part{}: {}", if part2 { "2" } else { "1" }, file);
+    type Input = MapInfo;
+    type Answer = usize;
 
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
-
-    let map = parse_map(&mut reader)?;
-    // println!("{:?}", map);
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_map(input)
+    }
 
-    let result = solve(&map, part2).ok_or("No solution")?;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve(input, false).ok_or(Error::no_solution(11, Part::Part1))
+    }
 
-    Ok(result)
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve(input, true).ok_or(Error::no_solution(11, Part::Part2))
+    }
 }
 
-fn parse_map<R: std::io::BufRead>(reader: &mut R) -> Result<MapInfo, Box<dyn std::error::Error>> {
-    let mut map = Vec::new();
-    let mut max_x = 0;
-    let mut non_empty_x = HashSet::new();
-    let mut empty_y = HashSet::new();
-
-    for (y, line) in reader.lines().enumerate() {
-        let line = line?;
-        let mut empty = true;
-
-        if line.len() > max_x {
-            max_x = line.len();
-        }
-
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                '#' => {
-                    map.push((x, y));
-                    empty = false;
-                    non_empty_x.insert(x);
-                }
-                '.' => {}
-                _ => return Err(format!("Invalid char: {}", c).into()),
-            }
-        }
-
-        if empty {
-            empty_y.insert(y);
-        }
-    }
+/// Parse galaxy image
+pub fn parse_map(input: &str) -> Result<MapInfo, Error> {
+    let image = Grid::parse(input, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })?;
 
-    let empty_x = (0..max_x).filter(|&x| !non_empty_x.contains(&x)).collect();
+    let map = image
+        .iter()
+        .filter(|(_, &galaxy)| galaxy)
+        .map(|(pos, _)| pos)
+        .collect();
+    let empty_x = (0..image.width())
+        .filter(|&x| !image.column(x).any(|&galaxy| galaxy))
+        .collect();
+    let empty_y = image
+        .rows()
+        .enumerate()
+        .filter(|(_, row)| !row.contains(&true))
+        .map(|(y, _)| y)
+        .collect();
 
     Ok(MapInfo {
         map,
@@ -86,15 +71,8 @@ fn parse_map<R: std::io::BufRead>(reader: &mut R) -> Result<MapInfo, Box<dyn std
 //     }
 // }
 
-fn abs_diff(v1: usize, v2: usize) -> usize {
-    if v1 < v2 {
-        v2 - v1
-    } else {
-        v1 - v2
-    }
-}
-
-fn solve(map: &MapInfo, part2: bool) -> Option<usize> {
+/// Sum of distances between all galaxy pairs after expanding empty space
+pub fn solve(map: &MapInfo, part2: bool) -> Option<usize> {
     let expanded_value = if part2 { 1000000 } else { 2 };
 
     let mut updated_map = Vec::new();
@@ -118,7 +96,7 @@ fn solve(map: &MapInfo, part2: bool) -> Option<usize> {
             updated_map
                 .iter()
                 .skip(i + 1)
-                .map(|(x2, y2)| abs_diff(*x1, *x2) + abs_diff(*y1, *y2))
+                .map(|(x2, y2)| x1.abs_diff(*x2) + y1.abs_diff(*y2))
                 .sum::<usize>()
         })
         .sum::<usize>()
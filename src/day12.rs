@@ -1,23 +1,27 @@
 use core::fmt::Debug;
-use regex::Regex;
-use std::{collections::HashMap, io::BufRead, usize};
+use num_bigint::BigUint;
 use rayon::prelude::*;
+use regex::Regex;
+use std::{io::BufRead, ops::Range};
 
-use crate::utils;
+use crate::error::Error;
+use crate::solution::Solution;
 
 const LINE_RE: &str = r"^(?P<modes>[.#?]+) (?P<groups>[0-9,]+)$";
 
+/// Condition of a single spring
 #[derive(PartialEq, Eq, Clone, Copy, Hash)]
-enum OperationalMode {
+pub enum OperationalMode {
     Good,
     Bad,
     Unknown,
 }
 
+/// Row of springs and sizes of its damaged groups
 #[derive(Clone)]
-struct Line {
-    modes: Vec<OperationalMode>,
-    groups: Vec<usize>,
+pub struct Line {
+    pub modes: Vec<OperationalMode>,
+    pub groups: Vec<usize>,
 }
 
 impl Debug for OperationalMode {
@@ -30,14 +34,35 @@ impl Debug for OperationalMode {
     }
 }
 
+impl OperationalMode {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(OperationalMode::Good),
+            '#' => Some(OperationalMode::Bad),
+            '?' => Some(OperationalMode::Unknown),
+            _ => None,
+        }
+    }
+}
+
+/// Parse modes given as `.#?` characters
+pub fn parse_modes(line: usize, text: &str) -> Result<Vec<OperationalMode>, Error> {
+    text.chars()
+        .map(|c| {
+            OperationalMode::from_char(c)
+                .ok_or_else(|| Error::parse(line, &c.to_string(), "Invalid mode"))
+        })
+        .collect()
+}
+
+/// Modes as `.#?` characters
+pub fn format_modes(modes: &[OperationalMode]) -> String {
+    modes.iter().map(|m| format!("{:?}", m)).collect()
+}
+
 impl Debug for Line {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let modes = self
-            .modes
-            .iter()
-            .map(|m| format!("{:?}", m))
-            .collect::<Vec<_>>()
-            .join("");
+        let modes = format_modes(&self.modes);
         let groups = self
             .groups
             .iter()
@@ -48,87 +73,96 @@ impl Debug for Line {
     }
 }
 
-pub fn day12() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day12")? {
-        handle(run(&file, false));
-        handle(run(&file, true));
+/// How rows are unfolded, number of copies and springs put between them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unfold {
+    pub factor: usize,
+    pub joiner: Vec<OperationalMode>,
+}
+
+impl Unfold {
+    pub fn new(factor: usize, joiner: Vec<OperationalMode>) -> Self {
+        Self { factor, joiner }
+    }
+
+    /// Rows are left as they are
+    pub fn none() -> Self {
+        Self::new(1, Vec::new())
+    }
+
+    /// Five copies joined by an unknown spring
+    pub fn part2() -> Self {
+        Self::new(5, vec![OperationalMode::Unknown])
     }
-    Ok(())
 }
 
-fn handle(result: Result<usize, Box<dyn std::error::Error>>) {
-    match result {
-        Ok(result) => println!("Result: {}", result),
-        Err(e) => println!("Error: {}", e),
+impl Line {
+    /// Copies of the row joined by `unfold.joiner`, with groups repeated for each copy
+    pub fn unfold(&self, unfold: &Unfold) -> Line {
+        let mut modes = Vec::new();
+        for i in 0..unfold.factor {
+            if i > 0 {
+                modes.extend(&unfold.joiner);
+            }
+            modes.extend(&self.modes);
+        }
+
+        Line {
+            modes,
+            groups: self.groups.repeat(unfold.factor),
+        }
     }
-    println!();
 }
 
-fn run(file: &str, part2: bool) -> Result<usize, Box<dyn std::error::Error>> {
-    println!("DAY12: Part{}: {}", if part2 { "2" } else { "1" }, file);
+/// Day 12: Hot Springs
+pub struct Day12;
 
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
+impl Solution for Day12 {
+    const DAY: u8 = 12;
 
-    let input: Vec<Line> = parse_input(&mut reader, part2)?;
-    // println!("{:?}", input);
+    type Input = Vec<Line>;
+    type Answer = BigUint;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(&mut input.as_bytes())
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        Ok(solve_any(input))
+    }
 
-    let result = solve(&input).ok_or("No solution")?;
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        let unfold = Unfold::part2();
+        let input: Vec<Line> = input.iter().map(|line| line.unfold(&unfold)).collect();
+        // println!("{:?}", input);
 
-    Ok(result)
+        Ok(solve_any(&input))
+    }
 }
 
-fn parse_input<R: std::io::BufRead>(
-    reader: &mut R,
-    part2: bool,
-) -> Result<Vec<Line>, Box<dyn std::error::Error>> {
+/// Parse spring rows
+pub fn parse_input<R: std::io::BufRead>(reader: &mut R) -> Result<Vec<Line>, Error> {
     let line_re = Regex::new(LINE_RE)?;
 
     let mut lines = Vec::new();
 
-    for line in reader.lines() {
+    for (i, line) in reader.lines().enumerate() {
         let line = line?;
 
-        let captures = line_re.captures(line.as_str()).ok_or("Invalid line")?;
+        let captures = line_re
+            .captures(line.as_str())
+            .ok_or_else(|| Error::parse(i + 1, &line, "Invalid row"))?;
 
         let (_, [modes, groups]) = captures.extract();
 
-        let mut modes = modes.to_string();
-        let mut groups = groups.to_string();
-
-        if part2 {
-            let modes2 = [
-                modes.as_str(),
-                modes.as_str(),
-                modes.as_str(),
-                modes.as_str(),
-                modes.as_str(),
-            ];
-            modes = modes2.join("?");
-
-            let groups2 = [
-                groups.as_str(),
-                groups.as_str(),
-                groups.as_str(),
-                groups.as_str(),
-                groups.as_str(),
-            ];
-            groups = groups2.join(",");
-        }
-
-        let modes = modes
-            .chars()
-            .map(|c| match c {
-                '.' => Ok(OperationalMode::Good),
-                '#' => Ok(OperationalMode::Bad),
-                '?' => Ok(OperationalMode::Unknown),
-                _ => Err("Invalid mode"),
-            })
-            .collect::<Result<Vec<OperationalMode>, _>>()?;
+        let modes = parse_modes(i + 1, modes)?;
 
         let groups = groups
             .split(',')
-            .map(|s| s.parse::<usize>())
+            .map(|s| {
+                s.parse::<usize>()
+                    .map_err(|e| Error::parse(i + 1, s, format!("Invalid group: {}", e)))
+            })
             .collect::<Result<Vec<usize>, _>>()?;
 
         lines.push(Line { modes, groups });
@@ -137,27 +171,23 @@ fn parse_input<R: std::io::BufRead>(
     Ok(lines)
 }
 
-fn solve(input: &[Line]) -> Option<usize> {
+/// Sum of the numbers of arrangements of all rows
+///
+/// Counts in `u128` and only starts over with `BigUint` if that overflows.
+pub fn solve_any(input: &[Line]) -> BigUint {
+    solve::<u128>(input)
+        .map(BigUint::from)
+        .or_else(|| solve::<BigUint>(input))
+        .unwrap_or_default()
+}
+
+/// Sum of the numbers of arrangements of all rows, `None` if it doesn't fit into `T`
+pub fn solve<T: Count>(input: &[Line]) -> Option<T> {
     // let len = input.len();
     input
         .par_iter()
-        .map(|line| {
-            backtrack_groups(
-                &mut HashMap::new(),
-                &line.modes,
-                &line.groups,
-                line.groups.iter().sum::<usize>() + line.groups.len() - 1,
-                line.modes
-                    .iter()
-                    .filter(|m| {
-                        **m == OperationalMode::Bad || **m == OperationalMode::Unknown
-                    })
-                    .count(),
-                line.groups.iter().sum::<usize>(),
-            )
-        })
-        .sum::<usize>()
-        .into()
+        .map(count)
+        .try_reduce(T::zero, |a, b| a.checked_add(&b))
 
     // Previous solution; worked fine for part 1
 
@@ -182,6 +212,24 @@ fn solve(input: &[Line]) -> Option<usize> {
     // Some(result)
 }
 
+/// Total numbers of arrangements of rows unfolded by each factor
+pub fn sweep(
+    input: &[Line],
+    factors: &[usize],
+    joiner: &[OperationalMode],
+) -> Vec<(usize, BigUint)> {
+    factors
+        .iter()
+        .map(|&factor| {
+            let unfold = Unfold::new(factor, joiner.to_vec());
+            let lines: Vec<Line> = input.iter().map(|line| line.unfold(&unfold)).collect();
+            // Big integers never overflow
+            let total = solve(&lines).unwrap_or_default();
+            (factor, total)
+        })
+        .collect()
+}
+
 // Previous solution; worked fine for part 1
 
 // fn calculate_groups(line: &Line) -> (Vec<usize>, Vec<usize>, usize) {
@@ -283,7 +331,142 @@ fn solve(input: &[Line]) -> Option<usize> {
 //     result
 // }
 
-// New solution; works for both parts
+// Previous solution; worked for both parts, but memoized on copies of the line
+
+// fn updated_min_len(min_len: usize, group: usize) -> usize {
+//     // Usize underflow would panic
+//     // This check prevents that
+//     if min_len > group + 1 {
+//         min_len - group - 1
+//     } else {
+//         0
+//     }
+// }
+
+// fn assume_bad(
+//     cache: &mut HashMap<(Vec<OperationalMode>, Vec<usize>), usize>,
+//     line: &[OperationalMode],
+//     groups: &[usize],
+//     min_len: usize,
+//     bad: usize,
+//     min_bad: usize,
+//     group: usize,
+// ) -> usize {
+//     if !check_group(line, group) {
+//         // Expected group is not present - this is not a valid solution
+//         0
+//     } else if line.len() == group {
+//         // Edge case - last group at the end of line
+//         // This is only required because recursive call would
+//         // cut line using &line[group+1..] which will panic if
+//         // line is not longer than group (otherwise the recursive
+//         // call would handle this case with ease)
+//         // Since we already know that the line will be empty
+//         // we can just check if there are any groups left
+//         // instead of calling recursive function
+//         if groups.len() == 1 {
+//             // Valid solution
+//             1
+//         } else {
+//             // We didn't match all groups
+//             0
+//         }
+//     } else {
+//         // All checks passed - we can place the group here
+//         // and continue recursively with the rest of the line
+//         backtrack_groups(
+//             cache,
+//             &line[group + 1..],
+//             &groups[1..],
+//             updated_min_len(min_len, group),
+//             bad - group,
+//             min_bad - group,
+//         )
+//     }
+// }
+
+// /// Count arrangements of `line` matching `groups`
+// ///
+// /// `min_len` is the minimal length required to fit all groups, `bad` the number of
+// /// possibly damaged springs and `min_bad` the number of damaged springs required.
+// pub fn backtrack_groups(
+//     cache: &mut HashMap<(Vec<OperationalMode>, Vec<usize>), usize>,
+//     line: &[OperationalMode],
+//     groups: &[usize],
+//     min_len: usize,
+//     bad: usize,
+//     min_bad: usize,
+// ) -> usize {
+//     // println!("{:?} {:?} {}", line, groups, min_len);
+
+//     if line.is_empty() {
+//         if groups.is_empty() {
+//             // Valid solution
+//             return 1;
+//         } else {
+//             // We didn't match all groups
+//             return 0;
+//         }
+//     }
+
+//     if bad < min_bad {
+//         // Not enough bad springs to fulfill all groups
+//         return 0;
+//     }
+
+//     if line.len() < min_len {
+//         // Line is too short to fit all groups
+//         return 0;
+//     }
+
+//     let state = (line.to_vec(), groups.to_vec());
+
+//     if let Some(&result) = cache.get(&state) {
+//         // We have already calculated result for this state
+//         return result;
+//     }
+
+//     let current = line[0];
+//     let group = groups.first();
+
+//     let result = match (current, group) {
+//         (OperationalMode::Good, _) => {
+//             // Current is good ('.')
+//             backtrack_groups(cache, &line[1..], groups, min_len, bad, min_bad)
+//         }
+//         (OperationalMode::Bad, None) => {
+//             // Current is bad ('#')
+//             // This marks the start of a group, but we don't have any groups left
+//             // Thus this is not a valid solution
+//             0
+//         }
+//         (OperationalMode::Bad, Some(&group)) => {
+//             // Current is bad ('#')
+//             // We have to match current group otherwise it is not a valid solution
+//             assume_bad(cache, line, groups, min_len, bad, min_bad, group)
+//         }
+//         (OperationalMode::Unknown, None) => {
+//             // Assume good ('.') for current
+//             backtrack_groups(cache, &line[1..], groups, min_len, bad, min_bad)
+//         }
+//         (OperationalMode::Unknown, Some(&group)) => {
+//             // Assume bad ('#') for current
+//             let mut result = assume_bad(cache, line, groups, min_len, bad, min_bad, group);
+
+//             // Assume good ('.') for current
+//             result += backtrack_groups(cache, &line[1..], groups, min_len, bad - 1, min_bad);
+
+//             result
+//         }
+//     };
+
+//     // Save result for this state
+//     cache.insert(state, result);
+
+//     result
+// }
+
+// New solution; works for large unfold factors
 
 fn check_group(line: &[OperationalMode], group: usize) -> bool {
     // Check if line begins with group of given size
@@ -311,131 +494,358 @@ fn check_group(line: &[OperationalMode], group: usize) -> bool {
     true
 }
 
-fn updated_min_len(min_len: usize, group: usize) -> usize {
-    // Usize underflow would panic
-    // This check prevents that
-    if min_len > group + 1 {
-        min_len - group - 1
-    } else {
+/// Number type arrangements are counted in
+pub trait Count: Clone + Send + Sync {
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// Sum of both numbers, `None` if it doesn't fit
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+}
+
+/// Only tells whether there is any arrangement
+impl Count for bool {
+    fn zero() -> Self {
+        false
+    }
+
+    fn one() -> Self {
+        true
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(*self || *other)
+    }
+}
+
+impl Count for usize {
+    fn zero() -> Self {
         0
     }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        usize::checked_add(*self, *other)
+    }
 }
 
-fn assume_bad(
-    cache: &mut HashMap<(Vec<OperationalMode>, Vec<usize>), usize>,
-    line: &[OperationalMode],
-    groups: &[usize],
-    min_len: usize,
-    bad: usize,
-    min_bad: usize,
-    group: usize,
-) -> usize {
-    if !check_group(line, group) {
-        // Expected group is not present - this is not a valid solution
+impl Count for u128 {
+    fn zero() -> Self {
         0
-    } else if line.len() == group {
-        // Edge case - last group at the end of line
-        // This is only required because recursive call would
-        // cut line using &line[group+1..] which will panic if
-        // line is not longer than group (otherwise the recursive
-        // call would handle this case with ease)
-        // Since we already know that the line will be empty
-        // we can just check if there are any groups left
-        // instead of calling recursive function
-        if groups.len() == 1 {
-            // Valid solution
-            1
-        } else {
-            // We didn't match all groups
-            0
-        }
-    } else {
-        // All checks passed - we can place the group here
-        // and continue recursively with the rest of the line
-        backtrack_groups(
-            cache,
-            &line[group + 1..],
-            &groups[1..],
-            updated_min_len(min_len, group),
-            bad - group,
-            min_bad - group,
-        )
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u128::checked_add(*self, *other)
     }
 }
 
-fn backtrack_groups(
-    cache: &mut HashMap<(Vec<OperationalMode>, Vec<usize>), usize>,
-    line: &[OperationalMode],
-    groups: &[usize],
-    min_len: usize,
-    bad: usize,
-    min_bad: usize,
-) -> usize {
-    // println!("{:?} {:?} {}", line, groups, min_len);
-
-    if line.is_empty() {
-        if groups.is_empty() {
-            // Valid solution
-            return 1;
+impl Count for BigUint {
+    fn zero() -> Self {
+        BigUint::default()
+    }
+
+    fn one() -> Self {
+        BigUint::from(1u8)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+}
+
+/// Arrangement counts of row suffixes, computed bottom-up over a flat table
+///
+/// Count of springs from `position` on matching groups from `group` on is stored at
+/// `(position % rows) * (groups + 1) + group`. Each position only depends on positions
+/// at most one group further, so keeping just the last few of them bounds the memory.
+struct Counts<T> {
+    width: usize,
+    rows: usize,
+    table: Vec<T>,
+    /// Groups that may have a count at each position, all other counts are 0
+    bands: Vec<Range<usize>>,
+}
+
+impl<T: Count> Counts<T> {
+    /// Counts of all positions of the line, `None` if some count doesn't fit into `T`
+    fn full(line: &Line) -> Option<Self> {
+        Self::new(line, line.modes.len() + 1)
+    }
+
+    /// Counts keeping only positions needed to continue, enough to get the count of the whole line
+    fn window(line: &Line) -> Option<Self> {
+        let max_group = line.groups.iter().copied().max().unwrap_or(0);
+        Self::new(line, (max_group + 2).min(line.modes.len() + 1))
+    }
+
+    fn new(line: &Line, rows: usize) -> Option<Self> {
+        let modes = &line.modes;
+        let groups = &line.groups;
+        let len = modes.len();
+        let width = groups.len() + 1;
+
+        // Possibly damaged springs from each position on,
+        // and length of the run of them starting there
+        let mut bad = vec![0; len + 1];
+        let mut run = vec![0; len + 1];
+        for position in (0..len).rev() {
+            if modes[position] != OperationalMode::Good {
+                bad[position] = bad[position + 1] + 1;
+                run[position] = run[position + 1] + 1;
+            } else {
+                bad[position] = bad[position + 1];
+            }
+        }
+
+        // Damaged springs required by groups from each group on,
+        // and minimal length to fit them
+        let mut min_bad = vec![0; width];
+        for group in (0..groups.len()).rev() {
+            min_bad[group] = min_bad[group + 1] + groups[group];
+        }
+        let min_len: Vec<usize> = (0..width)
+            .map(|group| (min_bad[group] + groups.len() - group).saturating_sub(1))
+            .collect();
+
+        let mut counts = Self {
+            width,
+            rows,
+            table: vec![T::zero(); rows * width],
+            bands: vec![0..0; len + 1],
+        };
+
+        // Both requirements only decrease with more groups matched and grow easier to
+        // fulfill further from the end, so the first satisfiable group only moves down.
+        // Groups before a position need space before it as well, so the last group
+        // that may already be matched only moves down too.
+        let mut first = width;
+        let mut last = width;
+        for position in (0..=len).rev() {
+            while first > 0
+                && bad[position] >= min_bad[first - 1]
+                && len - position >= min_len[first - 1]
+            {
+                first -= 1;
+            }
+            while last > 0 && min_bad[0] - min_bad[last - 1] + last - 1 > position + 1 {
+                last -= 1;
+            }
+            counts.bands[position] = first..last.max(first);
+
+            for group in counts.bands[position].clone() {
+                let count = if position == len {
+                    // Valid solution if all groups are matched
+                    if group == groups.len() {
+                        T::one()
+                    } else {
+                        T::zero()
+                    }
+                } else {
+                    let mut count = T::zero();
+                    if modes[position] != OperationalMode::Bad {
+                        // Assume good ('.') for current
+                        count = count.checked_add(&counts.get(position + 1, group))?;
+                    }
+                    if modes[position] != OperationalMode::Good {
+                        if let Some(&size) = groups.get(group) {
+                            // Assume bad ('#') for current, the group has to fit here
+                            if run[position] >= size
+                                && modes.get(position + size) != Some(&OperationalMode::Bad)
+                            {
+                                let next = counts.get((position + size + 1).min(len), group + 1);
+                                count = count.checked_add(&next)?;
+                            }
+                        }
+                    }
+                    count
+                };
+                let index = counts.index(position, group);
+                counts.table[index] = count;
+            }
+        }
+
+        Some(counts)
+    }
+
+    fn index(&self, position: usize, group: usize) -> usize {
+        (position % self.rows) * self.width + group
+    }
+
+    fn get(&self, position: usize, group: usize) -> T {
+        if self.bands[position].contains(&group) {
+            self.table[self.index(position, group)].clone()
         } else {
-            // We didn't match all groups
-            return 0;
+            T::zero()
         }
     }
+}
+
+/// Number of arrangements of the row, `None` if it doesn't fit into `T`
+pub fn count<T: Count>(line: &Line) -> Option<T> {
+    Some(Counts::window(line)?.get(0, 0))
+}
 
-    if bad < min_bad {
-        // Not enough bad springs to fulfill all groups
-        return 0;
+/// Row with springs that are the same in all arrangements filled in
+///
+/// `None` if the row has no arrangement.
+pub fn settle(line: &Line) -> Option<Vec<OperationalMode>> {
+    if count::<bool>(line) != Some(true) {
+        return None;
     }
 
-    if line.len() < min_len {
-        // Line is too short to fit all groups
-        return 0;
+    let mut modes = line.modes.clone();
+    let mut assumed = line.clone();
+    for (i, mode) in modes.iter_mut().enumerate() {
+        if *mode != OperationalMode::Unknown {
+            continue;
+        }
+
+        assumed.modes[i] = OperationalMode::Bad;
+        let bad = count::<bool>(&assumed) == Some(true);
+        assumed.modes[i] = OperationalMode::Good;
+        let good = count::<bool>(&assumed) == Some(true);
+        assumed.modes[i] = OperationalMode::Unknown;
+
+        // At least one of them is possible, the row has an arrangement
+        match (bad, good) {
+            (true, false) => *mode = OperationalMode::Bad,
+            (false, true) => *mode = OperationalMode::Good,
+            _ => (),
+        }
     }
 
-    let state = (line.to_vec(), groups.to_vec());
+    Some(modes)
+}
+
+/// Arrangements of a single row, counted and listed using counts of all row suffixes
+///
+/// Arrangements are rows with every unknown spring replaced by a good or bad one,
+/// ordered as `.#` strings, so `#` comes before `.`.
+pub struct Arrangements<'a> {
+    line: &'a Line,
+    counts: Counts<u128>,
+}
 
-    if let Some(&result) = cache.get(&state) {
-        // We have already calculated result for this state
-        return result;
+impl<'a> Arrangements<'a> {
+    /// `None` if the row has more than `u128::MAX` arrangements
+    pub fn new(line: &'a Line) -> Option<Self> {
+        Some(Self {
+            line,
+            counts: Counts::full(line)?,
+        })
     }
 
-    let current = line[0];
-    let group = groups.first();
+    /// Number of arrangements of the whole row
+    pub fn count(&self) -> u128 {
+        self.count_from(0, 0)
+    }
 
-    let result = match (current, group) {
-        (OperationalMode::Good, _) => {
-            // Current is good ('.')
-            backtrack_groups(cache, &line[1..], groups, min_len, bad, min_bad)
+    /// Number of arrangements of springs from `position` on, matching groups from `group` on
+    pub fn count_from(&self, position: usize, group: usize) -> u128 {
+        self.counts.get(position, group)
+    }
+
+    /// Ways to continue from `position` with groups from `group` on, in lexicographic order
+    ///
+    /// Each choice is made of springs it decides and position and group it continues with.
+    fn choices(&self, position: usize, group: usize) -> Vec<(Vec<OperationalMode>, usize, usize)> {
+        let modes = &self.line.modes;
+        let mut choices = Vec::new();
+
+        // Next group starts here
+        if modes[position] != OperationalMode::Good {
+            if let Some(&size) = self.line.groups.get(group) {
+                if check_group(&modes[position..], size) {
+                    let mut springs = vec![OperationalMode::Bad; size];
+                    if position + size < modes.len() {
+                        springs.push(OperationalMode::Good);
+                    }
+                    let next = position + springs.len();
+                    choices.push((springs, next, group + 1));
+                }
+            }
         }
-        (OperationalMode::Bad, None) => {
-            // Current is bad ('#')
-            // This marks the start of a group, but we don't have any groups left
-            // Thus this is not a valid solution
-            0
+
+        // Spring is good
+        if modes[position] != OperationalMode::Bad {
+            choices.push((vec![OperationalMode::Good], position + 1, group));
         }
-        (OperationalMode::Bad, Some(&group)) => {
-            // Current is bad ('#')
-            // We have to match current group otherwise it is not a valid solution
-            assume_bad(cache, line, groups, min_len, bad, min_bad, group)
+
+        choices
+    }
+
+    /// Arrangement number `k` in lexicographic order, starting from 0
+    pub fn nth(&self, k: u128) -> Option<Vec<OperationalMode>> {
+        if k >= self.count() {
+            return None;
         }
-        (OperationalMode::Unknown, None) => {
-            // Assume good ('.') for current
-            backtrack_groups(cache, &line[1..], groups, min_len, bad, min_bad)
+
+        let mut k = k;
+        let mut arrangement = Vec::new();
+        let (mut position, mut group) = (0, 0);
+
+        while position < self.line.modes.len() {
+            for (springs, next_position, next_group) in self.choices(position, group) {
+                let count = self.count_from(next_position, next_group);
+                if k < count {
+                    arrangement.extend(springs);
+                    (position, group) = (next_position, next_group);
+                    break;
+                }
+                k -= count;
+            }
         }
-        (OperationalMode::Unknown, Some(&group)) => {
-            // Assume bad ('#') for current
-            let mut result = assume_bad(cache, line, groups, min_len, bad, min_bad, group);
 
-            // Assume good ('.') for current
-            result += backtrack_groups(cache, &line[1..], groups, min_len, bad - 1, min_bad);
+        Some(arrangement)
+    }
 
-            result
+    /// All arrangements in lexicographic order, generated lazily
+    pub fn iter(&self) -> ArrangementsIter<'_, 'a> {
+        ArrangementsIter {
+            arrangements: self,
+            stack: vec![(Vec::new(), 0, 0)],
         }
-    };
+    }
+}
 
-    // Save result for this state
-    cache.insert(state, result);
+/// Iterator over arrangements of a row, created by [`Arrangements::iter`]
+pub struct ArrangementsIter<'s, 'a> {
+    arrangements: &'s Arrangements<'a>,
+    /// Partial arrangements with the position and group they continue from
+    stack: Vec<(Vec<OperationalMode>, usize, usize)>,
+}
 
-    result
+impl Iterator for ArrangementsIter<'_, '_> {
+    type Item = Vec<OperationalMode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let arrangements = self.arrangements;
+        while let Some((arrangement, position, group)) = self.stack.pop() {
+            if position == arrangements.line.modes.len() {
+                if group == arrangements.line.groups.len() {
+                    return Some(arrangement);
+                }
+                continue;
+            }
+
+            // Pushed in reverse to pop the first choice first,
+            // choices without any arrangement are never explored
+            for (springs, next_position, next_group) in
+                arrangements.choices(position, group).into_iter().rev()
+            {
+                if arrangements.count_from(next_position, next_group) > 0 {
+                    let mut arrangement = arrangement.clone();
+                    arrangement.extend(springs);
+                    self.stack.push((arrangement, next_position, next_group));
+                }
+            }
+        }
+        None
+    }
 }
@@ -1,73 +1,58 @@
-use itertools::Itertools;
-use std::io::BufRead;
+use crate::error::Error;
+use crate::solution::{Part, Solution};
+use crate::utils::Grid;
 
-use crate::utils;
+/// Day 13: Point of Incidence
+pub struct Day13;
 
-pub fn day13() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day13")? {
-        handle(run(&file));
-    }
-    Ok(())
-}
-
-fn handle(result: Result<(usize, usize), Box<dyn std::error::Error>>) {
-    match result {
-        Ok((result_p1, result_p2)) => {
-            println!("Result: Part1 = {} | Part2 = {}", result_p1, result_p2)
-        }
-        Err(e) => println!("Error: {}", e),
-    }
-    println!();
-}
-
-fn run(file: &str) -> Result<(usize, usize), Box<dyn std::error::Error>> {
-    println!("DAY13: {}", file);
+impl Solution for Day13 {
+    const DAY: u8 = 13;
 
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
+    type Input = Vec<Grid<char>>;
+    type Answer = usize;
 
-    let input: Vec<Vec<Vec<char>>> = parse_input(&mut reader)?;
-    // println!("{:?}", input);
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(input)
+    }
 
-    let result_p1 = solve_p1(&input).ok_or("No solution")?;
-    let result_p2 = solve_p2(&input).ok_or("No solution")?;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve_p1(input).ok_or(Error::no_solution(13, Part::Part1))
+    }
 
-    Ok((result_p1, result_p2))
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve_p2(input).ok_or(Error::no_solution(13, Part::Part2))
+    }
 }
 
-fn parse_input<R: std::io::BufRead>(
-    reader: &mut R,
-) -> Result<Vec<Vec<Vec<char>>>, Box<dyn std::error::Error>> {
-    let mut input = Vec::new();
-
-    let mut lines = Vec::new();
-
-    for line in reader.lines() {
-        let line = line?;
-
-        if line.is_empty() {
-            input.push(lines);
-            lines = Vec::new();
-        } else {
-            lines.push(line.chars().collect_vec());
+/// Parse patterns separated by empty lines
+pub fn parse_input(input: &str) -> Result<Vec<Grid<char>>, Error> {
+    let mut patterns = Vec::new();
+    let mut first_line = 0;
+
+    let lines = input.lines().collect::<Vec<_>>();
+    for group in lines.split(|line| line.is_empty()) {
+        if !group.is_empty() {
+            let pattern = Grid::parse(&group.join("\n"), Some).map_err(|mut e| {
+                e.line += first_line;
+                e
+            })?;
+            patterns.push(pattern);
         }
+        first_line += group.len() + 1;
     }
 
-    if !lines.is_empty() {
-        input.push(lines);
-    }
-
-    Ok(input)
+    Ok(patterns)
 }
 
-fn solve_group_p1(group: &[Vec<char>]) -> usize {
-    let mut possible_mirrors_x: Vec<usize> = (1..group[0].len()).collect();
-    let mut possible_mirrors_y: Vec<usize> = (1..group.len()).collect();
+/// Summarize reflection lines of a pattern
+pub fn solve_group_p1(group: &Grid<char>) -> usize {
+    let mut possible_mirrors_x: Vec<usize> = (1..group.width()).collect();
+    let mut possible_mirrors_y: Vec<usize> = (1..group.height()).collect();
 
     // println!("sx={:?}", possible_mirrors_x);
     // println!("sy={:?}", possible_mirrors_y);
 
-    for line in group {
+    for line in group.rows() {
         possible_mirrors_x.retain(|&mx| {
             // println!("x{}_inner_l={:?}", mx, line[..mx].iter().rev().collect::<Vec<_>>());
             // println!("x{}_inner_r={:?}", mx, line[mx..].iter().collect::<Vec<_>>());
@@ -80,15 +65,17 @@ fn solve_group_p1(group: &[Vec<char>]) -> usize {
         // println!("x={:?}", possible_mirrors_x);
     }
 
-    for x in 0..group[0].len() {
+    // Columns of the pattern as rows
+    let transposed = group.transpose();
+    for column in transposed.rows() {
         possible_mirrors_y.retain(|&my| {
-            // println!("y{}_inner_l={:?}", my, group[..my].iter().rev().map(|line| line[y]).collect::<Vec<_>>());
-            // println!("y{}_inner_r={:?}", my, group[my..].iter().map(|line| line[y]).collect::<Vec<_>>());
-            // println!("y{}_inner_a={:?}", my, group.iter().map(|line| line[y]).collect::<Vec<_>>());
-            group[my..]
+            // println!("y{}_inner_l={:?}", my, column[..my].iter().rev().collect::<Vec<_>>());
+            // println!("y{}_inner_r={:?}", my, column[my..].iter().collect::<Vec<_>>());
+            // println!("y{}_inner_a={:?}", my, column);
+            column[my..]
                 .iter()
-                .zip(group[..my].iter().rev())
-                .all(|(a, b)| a[x] == b[x])
+                .zip(column[..my].iter().rev())
+                .all(|(a, b)| a == b)
         });
         // println!("y={:?}", possible_mirrors_y);
     }
@@ -102,20 +89,19 @@ fn solve_group_p1(group: &[Vec<char>]) -> usize {
     result_x + result_y
 }
 
-fn solve_p1(input: &[Vec<Vec<char>>]) -> Option<usize> {
-    input
-        .iter()
-        .map(|group| solve_group_p1(group))
-        .sum::<usize>()
-        .into()
+/// Sum of summarized reflection lines of all patterns
+pub fn solve_p1(input: &[Grid<char>]) -> Option<usize> {
+    input.iter().map(solve_group_p1).sum::<usize>().into()
 }
 
-fn solve_group_p2(group: &[Vec<char>]) -> usize {
+/// Summarize reflection lines of a pattern with exactly one smudge
+pub fn solve_group_p2(group: &Grid<char>) -> usize {
     let mut possible_mirrors_x: Vec<(usize, bool)> =
-        (1..group[0].len()).map(|x| (x, false)).collect();
-    let mut possible_mirrors_y: Vec<(usize, bool)> = (1..group.len()).map(|y| (y, false)).collect();
+        (1..group.width()).map(|x| (x, false)).collect();
+    let mut possible_mirrors_y: Vec<(usize, bool)> =
+        (1..group.height()).map(|y| (y, false)).collect();
 
-    for line in group {
+    for line in group.rows() {
         possible_mirrors_x = possible_mirrors_x
             .into_iter()
             .filter_map(|(mx, fixed)| {
@@ -139,18 +125,20 @@ fn solve_group_p2(group: &[Vec<char>]) -> usize {
         // println!("x={:?}", possible_mirrors_x);
     }
 
-    for x in 0..group[0].len() {
+    // Columns of the pattern as rows
+    let transposed = group.transpose();
+    for column in transposed.rows() {
         possible_mirrors_y = possible_mirrors_y
             .into_iter()
             .filter_map(|(my, fixed)| {
-                let invalid_count = group[my..]
+                let invalid_count = column[my..]
                     .iter()
-                    .zip(group[..my].iter().rev())
-                    .filter(|(a, b)| a[x] != b[x])
+                    .zip(column[..my].iter().rev())
+                    .filter(|(a, b)| a != b)
                     .count();
-                // println!("y{}_inner_l={:?}", my, group[..my].iter().rev().map(|line| line[x]).collect::<Vec<_>>());
-                // println!("y{}_inner_r={:?}", my, group[my..].iter().map(|line| line[x]).collect::<Vec<_>>());
-                // println!("y{}_inner_a={:?}", my, group.iter().map(|line| line[x]).collect::<Vec<_>>());
+                // println!("y{}_inner_l={:?}", my, column[..my].iter().rev().collect::<Vec<_>>());
+                // println!("y{}_inner_r={:?}", my, column[my..].iter().collect::<Vec<_>>());
+                // println!("y{}_inner_a={:?}", my, column);
                 // println!("y{}_invalid_count={:?}", my, invalid_count);
                 match (fixed, invalid_count) {
                     (false, 0) => Some((my, false)),
@@ -178,10 +166,7 @@ fn solve_group_p2(group: &[Vec<char>]) -> usize {
     result_x + result_y
 }
 
-fn solve_p2(input: &[Vec<Vec<char>>]) -> Option<usize> {
-    input
-        .iter()
-        .map(|group| solve_group_p2(group))
-        .sum::<usize>()
-        .into()
+/// Sum of summarized reflection lines of all patterns with smudges fixed
+pub fn solve_p2(input: &[Grid<char>]) -> Option<usize> {
+    input.iter().map(solve_group_p2).sum::<usize>().into()
 }
@@ -1,61 +1,46 @@
 use itertools::Itertools;
-use std::{collections::HashMap, io::BufRead};
 
-use crate::utils;
+use crate::error::Error;
+use crate::solution::{Part, Solution};
+use crate::utils::{cycle, Grid};
 
-pub fn day14() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day14")? {
-        handle(run(&file));
-    }
-    Ok(())
-}
+/// Day 14: Parabolic Reflector Dish
+pub struct Day14;
 
-fn handle(result: Result<(usize, usize), Box<dyn std::error::Error>>) {
-    match result {
-        Ok((result_p1, result_p2)) => {
-            println!("Result: Part1 = {} | Part2 = {}", result_p1, result_p2)
-        }
-        Err(e) => println!("Error: {}", e),
-    }
-    println!();
-}
+impl Solution for Day14 {
+    const DAY: u8 = 14;
 
-fn run(file: &str) -> Result<(usize, usize), Box<dyn std::error::Error>> {
-    println!("DAY14: {}", file);
+    type Input = Grid<char>;
+    type Answer = usize;
 
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
-
-    let input: Vec<Vec<char>> = parse_input(&mut reader)?;
-    // println!("{:?}", input);
-
-    let result_p1 = solve_p1(&input).ok_or("No solution")?;
-    let result_p2 = solve_p2(&input).ok_or("No solution")?;
-
-    Ok((result_p1, result_p2))
-}
-
-fn parse_input<R: std::io::BufRead>(
-    reader: &mut R,
-) -> Result<Vec<Vec<char>>, Box<dyn std::error::Error>> {
-    let mut input = Vec::new();
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(input)
+    }
 
-    for line in reader.lines() {
-        let line = line?;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve_p1(input).ok_or(Error::no_solution(14, Part::Part1))
+    }
 
-        input.push(line.chars().collect_vec());
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve_p2(input).ok_or(Error::no_solution(14, Part::Part2))
     }
+}
 
-    Ok(input)
+/// Parse platform
+pub fn parse_input(input: &str) -> Result<Grid<char>, Error> {
+    Ok(Grid::parse(input, |c| {
+        matches!(c, '.' | '#' | 'O').then_some(c)
+    })?)
 }
 
-fn solve_p1(input: &[Vec<char>]) -> Option<usize> {
+/// Load on the north beams after tilting north
+pub fn solve_p1(input: &Grid<char>) -> Option<usize> {
     let mut result = 0;
 
-    let mut column_weights = (0..input[0].len()).map(|_| input.len()).collect_vec();
+    let mut column_weights = (0..input.width()).map(|_| input.height()).collect_vec();
 
-    for (y, line) in input.iter().enumerate() {
-        let line_weight = input.len() - y;
+    for (y, line) in input.rows().enumerate() {
+        let line_weight = input.height() - y;
         for (x, &c) in line.iter().enumerate() {
             match c {
                 '.' => (),
@@ -75,40 +60,41 @@ fn solve_p1(input: &[Vec<char>]) -> Option<usize> {
     Some(result)
 }
 
-fn rotate_map(input: &[Vec<char>]) -> Option<Vec<Vec<char>>> {
-    let width = input[0].len();
-    let height = input.len();
+/// Roll all round rocks north
+pub fn tilt_north(input: &Grid<char>) -> Option<Grid<char>> {
+    let mut result = Grid::filled(input.width(), input.height(), '.');
 
-    let mut result = vec![vec!['.'; height]; width];
+    let mut ceiling_cache = vec![0; input.width()];
 
-    let mut ceiling_cache = vec![0; width];
-
-    for (y, line) in input.iter().enumerate() {
-        for (x, &c) in line.iter().enumerate() {
-            match c {
-                '.' => (),
-                '#' => {
-                    ceiling_cache[x] = y + 1;
-                    result[x][height - 1 - y] = '#';
-                }
-                'O' => {
-                    // print!("{} ", column_weights[x]);
-                    result[x][height - 1 - ceiling_cache[x]] = 'O';
-                    ceiling_cache[x] += 1;
-                }
-                _ => return None,
+    for ((x, y), &c) in input.iter() {
+        match c {
+            '.' => (),
+            '#' => {
+                ceiling_cache[x] = y + 1;
+                result[(x, y)] = '#';
+            }
+            'O' => {
+                result[(x, ceiling_cache[x])] = 'O';
+                ceiling_cache[x] += 1;
             }
+            _ => return None,
         }
     }
 
     Some(result)
 }
 
-fn sum_weights(input: &[Vec<char>]) -> Option<usize> {
+/// Tilt north, then rotate clockwise
+pub fn rotate_map(input: &Grid<char>) -> Option<Grid<char>> {
+    Some(tilt_north(input)?.rotate_cw())
+}
+
+/// Load on the north beams
+pub fn sum_weights(input: &Grid<char>) -> Option<usize> {
     let mut result = 0;
 
-    for (y, line) in input.iter().enumerate() {
-        let line_weight = input.len() - y;
+    for (y, line) in input.rows().enumerate() {
+        let line_weight = input.height() - y;
         for &c in line {
             match c {
                 '.' => (),
@@ -124,36 +110,18 @@ fn sum_weights(input: &[Vec<char>]) -> Option<usize> {
     Some(result)
 }
 
-fn solve_p2(input: &[Vec<char>]) -> Option<usize> {
-    let mut input = input.to_vec();
-
-    let mut cache_index: HashMap<Vec<Vec<char>>, usize> = HashMap::new();
-    let mut cache_value: Vec<Vec<Vec<char>>> = Vec::new();
-
-    let cycles: usize = 1_000_000_000 * 4;
-
-    for i in 0..cycles {
-        input = rotate_map(&input)?;
-
-        if cache_index.contains_key(&input) {
-            // Loop detected, no need to continue
-
-            // println!("{}: {}", i, cache_index[&input]);
-            let cycle_start = cache_index[&input];
-            let cycle_length = i - cycle_start;
-            let cycle_remaining = cycles - cycle_start - 1;
-            input = cache_value[cycle_start + cycle_remaining % cycle_length].clone();
-            break;
-        } else {
-            cache_index.insert(input.clone(), i);
-            cache_value.push(input.clone());
-        }
-        // if i % 1000 == 0 {
-        //     println!("{}/{}", i, cycles);
-        // }
-    }
-
-    // println!("{}", input.iter().map(|l| l.iter().join("")).join("\n"));
+/// Load on the north beams after a billion spin cycles
+pub fn solve_p2(input: &Grid<char>) -> Option<usize> {
+    // Spin cycle is four quarter turns, each tilting north and rotating the platform.
+    // Invalid platform turns into `None`, which then stays `None` forever.
+    let rotations: usize = 1_000_000_000 * 4;
+    let input = cycle::state_after(
+        &Some(input.clone()),
+        |platform: &Option<Grid<char>>| platform.as_ref().and_then(rotate_map),
+        rotations,
+    )?;
+
+    // println!("{}", input);
 
     sum_weights(&input)
 }
@@ -1,73 +1,82 @@
 use itertools::Itertools;
-use std::io::Read;
 
-use crate::utils;
+use crate::error::{Error, ParseError};
+use crate::solution::{Part, Solution};
 
-pub fn day15() -> Result<(), Box<dyn std::error::Error>> {
-    for file in utils::input_files("day15")? {
-        handle(run(&file));
-    }
-    Ok(())
-}
+/// Day 15: Lens Library
+pub struct Day15;
 
-fn handle(result: Result<(usize, usize), Box<dyn std::error::Error>>) {
-    match result {
-        Ok((result_p1, result_p2)) => {
-            println!("Result: Part1 = {} | Part2 = {}", result_p1, result_p2)
-        }
-        Err(e) => println!("Error: {}", e),
-    }
-    println!();
-}
+impl Solution for Day15 {
+    const DAY: u8 = 15;
 
-fn run(file: &str) -> Result<(usize, usize), Box<dyn std::error::Error>> {
-    println!("DAY15: {}", file);
+    type Input = Vec<Step>;
+    type Answer = usize;
 
-    let file = std::fs::File::open(file)?;
-    let mut reader = std::io::BufReader::new(file);
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_steps(input)
+    }
 
-    let mut input = Vec::new();
-    reader.read_to_end(&mut input)?;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve_p1(input).ok_or(Error::no_solution(15, Part::Part1))
+    }
 
-    let result_p1 = solve_p1(&input).ok_or("No solution")?;
-    let result_p2 = solve_p2(&input).ok_or("No solution")?;
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error> {
+        solve_p2(input).ok_or(Error::no_solution(15, Part::Part2))
+    }
+}
 
-    Ok((result_p1, result_p2))
+/// Single step of the initialization sequence
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// HASH of the whole step, as part 1 reads it
+    pub hash: usize,
+    /// HASH of the label, which is the box the lens goes to
+    pub box_id: usize,
+    pub label: String,
+    pub action: Option<u8>,
+    pub value: Option<u8>,
 }
 
-fn solve_p1(input: &[u8]) -> Option<usize> {
-    input
-        .split(|&c| c == b',')
-        .map(|input| {
-            let mut v = 0;
-            for &c in input {
-                if c == b'\n' {
-                    continue;
-                }
-                v += c as usize;
-                v *= 17;
-                v %= 256;
-            }
-            v
-        })
-        .sum::<usize>()
-        .into()
+/// HASH of `bytes` continuing from `hash`, newlines are ignored
+fn extend_hash(hash: usize, bytes: &[u8]) -> usize {
+    let mut v = hash;
+    for &c in bytes {
+        if c == b'\n' {
+            continue;
+        }
+        v += c as usize;
+        v *= 17;
+        v %= 256;
+    }
+    v
 }
 
-fn solve_p2(input: &[u8]) -> Option<usize> {
+/// Sum of HASH values of all steps
+pub fn solve_p1(steps: &[Step]) -> Option<usize> {
+    steps.iter().map(|step| step.hash).sum::<usize>().into()
+}
+
+/// Parse comma separated steps
+pub fn parse_steps(input: &str) -> Result<Vec<Step>, Error> {
     input
-        .split(|&c| c == b',')
-        .map(|input| {
-            let mut hash = 0;
+        .split(',')
+        .map(|step| {
             let mut label = String::new();
             let mut reading_action = false;
             let mut action = None;
             let mut value = None;
-            for &c in input {
+            for c in step.bytes() {
                 match (reading_action, c) {
                     (_, b'\n') => (),
                     (true, _) => {
-                        assert!(c.is_ascii_digit());
+                        if !c.is_ascii_digit() {
+                            return Err(ParseError::within_input(
+                                input,
+                                step.trim_end(),
+                                "Invalid focal length",
+                            )
+                            .into());
+                        }
                         value = Some(c - b'0');
                     }
                     (false, b'=' | b'-') => {
@@ -76,16 +85,26 @@ fn solve_p2(input: &[u8]) -> Option<usize> {
                     }
                     (false, _) => {
                         label.push(c as char);
-                        hash += c as usize;
-                        hash *= 17;
-                        hash %= 256;
                     }
                 }
             }
-            (hash, label, action, value)
+            Ok(Step {
+                hash: extend_hash(0, step.as_bytes()),
+                box_id: extend_hash(0, label.as_bytes()),
+                label,
+                action,
+                value,
+            })
         })
-        .sorted_by(|e1, e2| e1.0.cmp(&e2.0))
-        .group_by(|e| e.0)
+        .collect()
+}
+
+/// Focusing power of the lens configuration
+pub fn solve_p2(steps: &[Step]) -> Option<usize> {
+    steps
+        .iter()
+        .sorted_by(|e1, e2| e1.box_id.cmp(&e2.box_id))
+        .group_by(|e| e.box_id)
         .into_iter()
         .map(|(hash, group)| {
             // First attempt - process in reverse order
@@ -115,15 +134,21 @@ fn solve_p2(input: &[u8]) -> Option<usize> {
 
             let mut contents = Vec::new();
 
-            for (_, label, action, value) in group {
+            for Step {
+                label,
+                action,
+                value,
+                ..
+            } in group
+            {
                 let index = contents
                     .iter()
                     .enumerate()
-                    .filter(|(_, (l, _))| l == &label)
+                    .filter(|(_, (l, _))| *l == label)
                     .map(|e| e.0)
                     .next();
 
-                match (action, value, index) {
+                match (*action, *value, index) {
                     (Some(b'='), Some(value), Some(index)) => {
                         contents[index].1 = value;
                     }
@@ -0,0 +1,179 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::solution::Part;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Regex(regex::Error),
+    Pattern(glob::PatternError),
+    /// Input pattern didn't match any file
+    NoInputs(String),
+    Parse(ParseError),
+    NoSolution {
+        day: u8,
+        part: Part,
+    },
+}
+
+/// Input that couldn't be parsed and where it was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Input file, filled in by whoever knows where the input came from
+    pub file: Option<String>,
+    /// Line number starting from 1
+    pub line: usize,
+    /// Column number starting from 1, if known
+    pub column: Option<usize>,
+    /// Offending text
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, text: &str, message: impl Into<String>) -> Self {
+        Self {
+            file: None,
+            line,
+            column: None,
+            text: text.to_owned(),
+            message: message.into(),
+        }
+    }
+
+    /// Error about `text`, which is a slice of the `line_text` found on line `line`
+    pub fn within(line: usize, line_text: &str, text: &str, message: impl Into<String>) -> Self {
+        let mut error = Self::new(line, text, message);
+        let offset = (text.as_ptr() as usize).wrapping_sub(line_text.as_ptr() as usize);
+        if offset <= line_text.len() {
+            error.column = Some(line_text[..offset].chars().count() + 1);
+        }
+        error
+    }
+
+    /// Error about `text`, which is a slice of the whole `input`
+    pub fn within_input(input: &str, text: &str, message: impl Into<String>) -> Self {
+        let offset = (text.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+        let mut error = Self::at_offset(input, offset, message);
+        error.text = text.to_owned();
+        error
+    }
+
+    /// Error about the line containing byte `offset` of the whole `input`
+    pub fn at_offset(input: &str, offset: usize, message: impl Into<String>) -> Self {
+        let offset = offset.min(input.len());
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+
+        let mut error = Self::new(
+            input[..line_start].matches('\n').count() + 1,
+            &input[line_start..line_end],
+            message,
+        );
+        error.column = Some(input[line_start..offset].chars().count() + 1);
+        error
+    }
+}
+
+impl Error {
+    pub fn parse(line: usize, text: &str, message: impl Into<String>) -> Self {
+        Error::Parse(ParseError::new(line, text, message))
+    }
+
+    pub fn no_solution(day: u8, part: Part) -> Self {
+        Error::NoSolution { day, part }
+    }
+
+    /// Attach input file name to parse errors
+    pub fn with_file(self, file: &str) -> Self {
+        match self {
+            Error::Parse(mut error) => {
+                error.file = Some(file.to_owned());
+                Error::Parse(error)
+            }
+            error => error,
+        }
+    }
+}
+
+/// Parse number `text` found in `line_text` on line `line`
+pub fn parse_number<T>(line: usize, line_text: &str, text: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.parse::<T>().map_err(|e| {
+        ParseError::within(line, line_text, text, format!("Invalid number: {}", e)).into()
+    })
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}",
+            self.file.as_deref().unwrap_or("<input>"),
+            self.line
+        )?;
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+        write!(f, ": {}: {:?}", self.message, self.text)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Regex(e) => write!(f, "Invalid regex: {}", e),
+            Error::Pattern(e) => write!(f, "Invalid input pattern: {}", e),
+            Error::NoInputs(pattern) => write!(f, "No inputs match {}", pattern),
+            Error::Parse(e) => write!(f, "Parse error: {}", e),
+            Error::NoSolution { day, part } => write!(f, "No solution for day {} {}", day, part),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Regex(e) => Some(e),
+            Error::Pattern(e) => Some(e),
+            Error::Parse(e) => Some(e),
+            Error::NoInputs(_) | Error::NoSolution { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(e: regex::Error) -> Self {
+        Error::Regex(e)
+    }
+}
+
+impl From<glob::PatternError> for Error {
+    fn from(e: glob::PatternError) -> Self {
+        Error::Pattern(e)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
@@ -0,0 +1,281 @@
+use std::path::Path;
+
+use aoc_2023::day10::AreaMethod;
+use aoc_2023::solution::Solution;
+use aoc_2023::utils::interval::IntervalSet;
+use aoc_2023::utils::{self, STDIN_INPUT};
+use aoc_2023::{day05, day08, day10, day12, nonogram};
+
+use crate::cli::{Day05Args, Day08Args, Day10Args, Day12Args, NonogramArgs};
+use crate::fetch;
+
+/// Read the given input, or the downloaded input of `day` if there is none
+///
+/// Returns the input label used in messages together with the input itself.
+fn read_input(
+    input_dir: &Path,
+    day: u8,
+    input: &Option<String>,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let file = match input {
+        Some(file) => file.clone(),
+        None => fetch::input_path(input_dir, day)
+            .to_str()
+            .ok_or("Invalid input directory")?
+            .to_owned(),
+    };
+    let input = utils::read_input(&file)?;
+    Ok((utils::input_label(&file).to_owned(), input))
+}
+
+/// Print output to stdout if `file` is `-`, otherwise write it to `file`
+fn write_output(file: &str, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if file == STDIN_INPUT {
+        print!("{}", output);
+    } else {
+        std::fs::write(file, output)?;
+    }
+    Ok(())
+}
+
+/// Trace the lowest or the given location back to the seeds it comes from
+pub fn day05(input_dir: &Path, args: &Day05Args) -> Result<bool, Box<dyn std::error::Error>> {
+    let (label, input) = read_input(input_dir, 5, &args.input)?;
+    let day05::Almanac { initial, map } = day05::Day05
+        .parse(&input)
+        .map_err(|e| e.with_file(&label))?;
+    let name = initial.name;
+
+    let target = day05::TARGET_CATEGORY;
+    let chain = day05::find_chain(&map, &name, target)
+        .ok_or(format!("No conversion from {} to {}", name, target))?;
+    let sources = if args.ranges {
+        initial.pair_ranges
+    } else {
+        initial.ranges
+    };
+
+    let (location, found) = match args.location {
+        Some(location) => {
+            let targets = IntervalSet::from(location..location.saturating_add(1));
+            let found = day05::find_sources(&map, &chain, &sources, &targets);
+            (location, found)
+        }
+        None => day05::min_sources(&map, &chain, &sources).ok_or(format!("No {}s", name))?,
+    };
+
+    println!("DAY05: {}", label);
+    println!("Chain: {}", chain.join(" -> "));
+
+    if found.is_empty() {
+        println!("No {} leads to {} {}", name, target, location);
+        return Ok(false);
+    }
+
+    println!("{} {} comes from:", target, location);
+    for range in found.ranges() {
+        let trace = day05::trace(&map, &chain, range.start)
+            .into_iter()
+            .map(|(category, value)| format!("{} {}", category, value))
+            .collect::<Vec<_>>();
+        println!("{:?} ({} {}s)", range, range.end - range.start, name);
+        println!("    {}", trace.join(", "));
+    }
+
+    Ok(true)
+}
+
+/// Solve day 8 with custom start and end nodes and describe the ghost loops
+pub fn day08(input_dir: &Path, args: &Day08Args) -> Result<bool, Box<dyn std::error::Error>> {
+    let (label, input) = read_input(input_dir, 8, &args.input)?;
+
+    let diagnostics =
+        day08::validate(&input, &args.start, &args.end).map_err(|e| e.with_file(&label))?;
+    if diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+        for diagnostic in diagnostics.iter().filter(|d| d.is_error()) {
+            println!("Error: {}: {}", label, diagnostic);
+        }
+        return Ok(false);
+    }
+
+    let (path, network) =
+        day08::parse_map(&input, &args.start, &args.end).map_err(|e| e.with_file(&label))?;
+    let cycles = day08::find_cycles(&path, &network);
+    let result = day08::solve(&cycles);
+
+    if let Some(file) = &args.dot {
+        write_output(file, &day08::to_dot(&path, &network, &cycles))?;
+        if file == STDIN_INPUT {
+            // Keep the printed graph valid
+            return Ok(result.is_some());
+        }
+    }
+
+    println!(
+        "DAY08: {} ({} nodes, {} starts, {} ends)",
+        label,
+        network.nodes.len(),
+        network.start_ids.len(),
+        network.end_ids.len()
+    );
+    for diagnostic in &diagnostics {
+        println!("Warning: {}", diagnostic);
+    }
+    for (&start_id, actor) in network.start_ids.iter().zip(&cycles) {
+        println!(
+            "{}: loop of {} steps after {} steps, end nodes at {:?} before and {:?} in the loop",
+            network.names[start_id],
+            actor.length,
+            actor.offset,
+            actor.prefix_end_indexes,
+            actor.loop_end_indexes
+        );
+    }
+
+    match result {
+        Some(steps) => println!("All ghosts stand on end nodes after {} steps", steps),
+        None => println!("Ghosts never stand on end nodes at the same time"),
+    }
+
+    Ok(result.is_some())
+}
+
+/// Count enclosed tiles with the chosen method and check the other one agrees
+pub fn day10(input_dir: &Path, args: &Day10Args) -> Result<bool, Box<dyn std::error::Error>> {
+    let (label, input) = read_input(input_dir, 10, &args.input)?;
+    let map = day10::parse_pipe_map(&input).map_err(|e| e.with_file(&label))?;
+    let tiles = day10::find_loop(&map).ok_or("Start tile is not part of a loop")?;
+    let vertices = day10::loop_vertices(&map, &tiles);
+
+    println!(
+        "DAY10: {} (loop of {} tiles, {} corners, start tile is {})",
+        label,
+        tiles.len(),
+        vertices.len(),
+        map.map[map.start]
+    );
+
+    let other = match args.area {
+        AreaMethod::FloodFill => AreaMethod::Shoelace,
+        AreaMethod::Shoelace => AreaMethod::FloodFill,
+    };
+    let result = day10::enclosed_tiles(&map, args.area);
+    let check = day10::enclosed_tiles(&map, other);
+
+    match (result, check) {
+        (Some(count), Some(other_count)) => {
+            println!("{}: {} enclosed tiles", args.area, count);
+            if count == other_count {
+                println!("{} agrees", other);
+            } else {
+                println!("{} disagrees: {} enclosed tiles", other, other_count);
+            }
+        }
+        (Some(count), None) => {
+            println!("{}: {} enclosed tiles", args.area, count);
+            println!("{} can't tell inside from outside", other);
+        }
+        (None, Some(other_count)) => {
+            println!("{}: can't tell inside from outside", args.area);
+            println!("{} gives {} enclosed tiles", other, other_count);
+        }
+        (None, None) => println!("Neither method can tell inside from outside"),
+    }
+
+    // Fine as long as some method has a count and nothing disagrees
+    let disagree = result.is_some() && check.is_some() && result != check;
+    Ok((result.is_some() || check.is_some()) && !disagree)
+}
+
+/// List arrangements of damaged springs, or a single one by its index
+///
+/// With several unfold factors, report total counts of each of them instead.
+pub fn day12(input_dir: &Path, args: &Day12Args) -> Result<bool, Box<dyn std::error::Error>> {
+    let (label, input) = read_input(input_dir, 12, &args.input)?;
+    let joiner = day12::parse_modes(1, &args.joiner).map_err(|e| e.with_file("joiner"))?;
+    let lines = day12::parse_input(&mut input.as_bytes()).map_err(|e| e.with_file(&label))?;
+
+    let rows: Vec<(usize, &day12::Line)> = match args.row {
+        Some(row) => vec![(
+            row,
+            row.checked_sub(1)
+                .and_then(|i| lines.get(i))
+                .ok_or(format!("No row {}", row))?,
+        )],
+        None => lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .collect(),
+    };
+
+    println!("DAY12: {}", label);
+
+    if let [factor] = args.unfold[..] {
+        let unfold = day12::Unfold::new(factor, joiner);
+        let mut found = true;
+        for (row, line) in rows {
+            let line = line.unfold(&unfold);
+            let Some(arrangements) = day12::Arrangements::new(&line) else {
+                println!("Row {}: {:?} has too many arrangements to list", row, line);
+                found = false;
+                continue;
+            };
+            let count = arrangements.count();
+            println!("Row {}: {:?} has {} arrangements", row, line, count);
+
+            match args.nth {
+                Some(k) => match arrangements.nth(k) {
+                    Some(arrangement) => println!("    {}", day12::format_modes(&arrangement)),
+                    None => {
+                        println!("    No arrangement {}", k);
+                        found = false;
+                    }
+                },
+                None => {
+                    for arrangement in arrangements.iter().take(args.limit) {
+                        println!("    {}", day12::format_modes(&arrangement));
+                    }
+                    if count > args.limit as u128 {
+                        println!("    ... {} more", count - args.limit as u128);
+                    }
+                }
+            }
+        }
+        return Ok(found);
+    }
+
+    let lines: Vec<day12::Line> = rows.into_iter().map(|(_, line)| line.clone()).collect();
+    for (factor, total) in day12::sweep(&lines, &args.unfold, &joiner) {
+        println!("Unfolded {}x: {} arrangements", factor, total);
+    }
+
+    Ok(true)
+}
+
+/// Solve a nonogram given by its clues and print the picture
+pub fn nonogram(args: &NonogramArgs) -> Result<bool, Box<dyn std::error::Error>> {
+    let input = utils::read_input(&args.input)?;
+    let label = utils::input_label(&args.input);
+    let clues = nonogram::parse_clues(&input).map_err(|e| e.with_file(label))?;
+
+    println!(
+        "NONOGRAM: {} ({}x{})",
+        label,
+        clues.columns.len(),
+        clues.rows.len()
+    );
+
+    match nonogram::solve(&clues) {
+        Some(picture) => {
+            for row in picture.rows() {
+                println!("{}", day12::format_modes(row));
+            }
+            Ok(true)
+        }
+        None => {
+            println!("No solution");
+            Ok(false)
+        }
+    }
+}
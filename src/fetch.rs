@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+
+use aoc_2023::solution::Runner;
+
+use crate::cli::FetchArgs;
+use crate::client::Client;
+use crate::config::Config;
+
+/// Name of the file in the input directory remembering time of the last request
+pub const STATE_FILE: &str = ".last-request";
+
+/// Path of the cached puzzle input of `day`
+pub fn input_path(input_dir: &Path, day: u8) -> PathBuf {
+    input_dir.join(format!("day{:02}.txt", day))
+}
+
+/// Download inputs of selected days that are not cached yet
+pub fn fetch(
+    registry: &[Box<dyn Runner>],
+    config: &Config,
+    input_dir: &Path,
+    args: &FetchArgs,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let days = match args.days.days() {
+        Some(days) => days,
+        None => registry.iter().map(|runner| runner.day()).collect(),
+    };
+
+    std::fs::create_dir_all(input_dir)?;
+    let client = Client::new(config, input_dir.join(STATE_FILE))?;
+
+    let mut ok = true;
+
+    for day in days {
+        let path = input_path(input_dir, day);
+        if path.exists() && !args.force {
+            println!("DAY{:02}: cached in {}", day, path.display());
+            continue;
+        }
+
+        match client.input(day) {
+            Ok(input) => {
+                std::fs::write(&path, input)?;
+                println!("DAY{:02}: saved to {}", day, path.display());
+            }
+            Err(e) => {
+                println!("DAY{:02}: Error: {}", day, e);
+                ok = false;
+            }
+        }
+    }
+
+    Ok(ok)
+}
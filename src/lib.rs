@@ -0,0 +1,48 @@
+//! Advent of Code 2023 solutions
+//!
+//! Every day implements [`solution::Solution`], parsing its input from a string
+//! and solving both parts. Parsed input types and the underlying solve functions
+//! are exposed by the day modules for reuse outside of the puzzles.
+
+use crate::solution::Runner;
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod error;
+pub mod nonogram;
+pub mod solution;
+pub mod utils;
+
+/// All implemented days - new days are plugged in here
+pub fn registry() -> Vec<Box<dyn Runner>> {
+    vec![
+        Box::new(day01::Day01),
+        Box::new(day02::Day02),
+        Box::new(day03::Day03),
+        Box::new(day04::Day04),
+        Box::new(day05::Day05),
+        Box::new(day06::Day06),
+        Box::new(day07::Day07),
+        Box::new(day08::Day08),
+        Box::new(day09::Day09),
+        Box::new(day10::Day10),
+        Box::new(day11::Day11),
+        Box::new(day12::Day12),
+        Box::new(day13::Day13),
+        Box::new(day14::Day14),
+        Box::new(day15::Day15),
+    ]
+}
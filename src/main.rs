@@ -1,42 +1,146 @@
-use std::result;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod utils;
-
-fn handle(result: result::Result<(), Box<dyn std::error::Error>>) {
-    if let Err(e) = result {
-        println!("Error: {}", e);
+use aoc_2023::solution::{self, Runner};
+use aoc_2023::utils::{self, Parts};
+use clap::Parser;
+use std::path::Path;
+use std::process::ExitCode;
+
+use crate::cli::{Cli, Command, Format, RunArgs, SelectArgs};
+use crate::config::Config;
+
+mod bench;
+mod cli;
+mod client;
+mod config;
+mod explore;
+mod fetch;
+mod report;
+mod submit;
+mod table;
+mod verify;
+
+fn run_file(runner: &dyn Runner, file: &str, parts: Parts) -> bool {
+    print!("DAY{:02}: {}", runner.day(), utils::input_label(file));
+
+    let results = solution::solve_file(runner, file, parts);
+
+    let ok = match results {
+        Ok(results) => {
+            println!(" (parse {:.2?})", results.parse_time);
+            let mut ok = true;
+            for result in results.parts {
+                match result.answer {
+                    Ok(answer) => println!("{}: {} ({:.2?})", result.part, answer, result.time),
+                    Err(e) => {
+                        println!("{}: Error: {} ({:.2?})", result.part, e, result.time);
+                        ok = false;
+                    }
+                }
+                for warning in &result.warnings {
+                    println!("{}: Warning: {}", result.part, warning);
+                }
+            }
+            ok
+        }
+        Err(e) => {
+            println!();
+            println!("Error: {}", e);
+            false
+        }
+    };
+    println!();
+    ok
+}
+
+/// Runners of selected days paired with the input files they should solve
+type Selection<'a> = Vec<(&'a dyn Runner, Vec<String>)>;
+
+fn select_inputs<'a>(
+    registry: &'a [Box<dyn Runner>],
+    input_dir: &Path,
+    args: &SelectArgs,
+) -> Result<Selection<'a>, Box<dyn std::error::Error>> {
+    let days = match args.days.days() {
+        Some(days) => days,
+        None => registry.iter().map(|runner| runner.day()).collect(),
+    };
+
+    let mut selected = Vec::new();
+
+    for day in days {
+        let runner = registry
+            .iter()
+            .find(|runner| runner.day() == day)
+            .ok_or(format!("Day {} is not implemented", day))?;
+
+        let files = if args.input.is_empty() {
+            utils::input_files(input_dir, &format!("day{:02}", day)).map_err(|e| {
+                format!("Cannot read input directory {}: {}", input_dir.display(), e)
+            })?
+        } else {
+            utils::expand_inputs(&args.input)?
+        };
+
+        selected.push((runner.as_ref(), files));
     }
+
+    Ok(selected)
+}
+
+fn run(
+    registry: &[Box<dyn Runner>],
+    input_dir: &Path,
+    args: &RunArgs,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let parts = args.select.parts();
+
+    let mut ok = true;
+    let mut records = Vec::new();
+
+    for (runner, files) in select_inputs(registry, input_dir, &args.select)? {
+        for file in files {
+            if args.format == Format::Text {
+                ok &= run_file(runner, &file, parts);
+            } else {
+                records.extend(report::solve_file(runner, &file, parts));
+            }
+        }
+    }
+
+    match args.format {
+        Format::Text => (),
+        Format::Json => report::print_json(&records)?,
+        Format::Csv => report::print_csv(&records)?,
+    }
+
+    Ok(ok && records.iter().all(|record| record.is_ok()))
 }
 
-fn main() {
-    handle(day01::day01());
-    handle(day02::day02());
-    handle(day03::day03());
-    handle(day04::day04());
-    handle(day05::day05());
-    handle(day06::day06());
-    handle(day07::day07());
-    handle(day08::day08());
-    handle(day09::day09());
-    handle(day10::day10());
-    handle(day11::day11());
-    handle(day12::day12());
-    handle(day13::day13());
-    handle(day14::day14());
-    handle(day15::day15());
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let registry = aoc_2023::registry();
+
+    let result = match cli.command {
+        Some(Command::Run(args)) => run(&registry, &cli.input_dir, &args),
+        Some(Command::Verify(args)) => verify::verify(&registry, &cli.input_dir, &args),
+        Some(Command::Bench(args)) => bench::bench(&registry, &cli.input_dir, &args),
+        Some(Command::Fetch(args)) => Config::load(&cli.config)
+            .and_then(|config| fetch::fetch(&registry, &config, &cli.input_dir, &args)),
+        Some(Command::Submit(args)) => Config::load(&cli.config)
+            .and_then(|config| submit::submit(&registry, &config, &cli.input_dir, &args)),
+        Some(Command::Day05(args)) => explore::day05(&cli.input_dir, &args),
+        Some(Command::Day08(args)) => explore::day08(&cli.input_dir, &args),
+        Some(Command::Day10(args)) => explore::day10(&cli.input_dir, &args),
+        Some(Command::Day12(args)) => explore::day12(&cli.input_dir, &args),
+        Some(Command::Nonogram(args)) => explore::nonogram(&args),
+        None => run(&registry, &cli.input_dir, &RunArgs::default()),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            println!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+
+use crate::day12::{self, Line, OperationalMode};
+use crate::error::{parse_number, Error};
+use crate::utils::grid::Pos;
+use crate::utils::Grid;
+
+/// Sizes of filled groups of every row and column of a nonogram
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Clues {
+    pub rows: Vec<Vec<usize>>,
+    pub columns: Vec<Vec<usize>>,
+}
+
+/// Picture of a nonogram, filled cells are bad springs and empty cells good ones
+pub type Picture = Grid<OperationalMode>;
+
+/// Row `y` or column `x` of the picture
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Lane {
+    Row(usize),
+    Column(usize),
+}
+
+/// Parse clues of rows, an empty line and clues of columns
+///
+/// Each line lists sizes of filled groups separated by commas, `0` stands for an empty line.
+pub fn parse_clues(input: &str) -> Result<Clues, Error> {
+    let mut rows = Vec::new();
+    let mut columns = Vec::new();
+    let mut section = 0;
+
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            // Blank lines end a section, but only after some clues
+            if (section == 0 && !rows.is_empty()) || (section == 1 && !columns.is_empty()) {
+                section += 1;
+            }
+            continue;
+        }
+
+        let mut groups = line
+            .split(',')
+            .map(|text| parse_number::<usize>(i + 1, line, text.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        groups.retain(|&group| group > 0);
+
+        match section {
+            0 => rows.push(groups),
+            1 => columns.push(groups),
+            _ => return Err(Error::parse(i + 1, line, "Unexpected clues after columns")),
+        }
+    }
+
+    if columns.is_empty() {
+        let last = input.lines().last().unwrap_or_default();
+        let lines = input.lines().count();
+        return Err(Error::parse(lines, last, "Missing clues of columns"));
+    }
+
+    Ok(Clues { rows, columns })
+}
+
+/// Solve the nonogram, `None` if it has no solution
+///
+/// Rows and columns are settled using day 12 row logic until nothing changes,
+/// then the first unknown cell is guessed and guesses leading to contradictions are undone.
+pub fn solve(clues: &Clues) -> Option<Picture> {
+    let picture = Grid::filled(
+        clues.columns.len(),
+        clues.rows.len(),
+        OperationalMode::Unknown,
+    );
+    search(clues, picture)
+}
+
+fn search(clues: &Clues, mut picture: Picture) -> Option<Picture> {
+    propagate(clues, &mut picture)?;
+
+    let Some(pos) = picture.find(|mode| *mode == OperationalMode::Unknown) else {
+        // Every line is settled, so every line matches its clue
+        return Some(picture);
+    };
+
+    for mode in [OperationalMode::Bad, OperationalMode::Good] {
+        let mut guess = picture.clone();
+        guess[pos] = mode;
+        if let Some(solved) = search(clues, guess) {
+            return Some(solved);
+        }
+    }
+
+    None
+}
+
+/// Settle lanes until no cell changes, `None` if some lane can't be filled
+fn propagate(clues: &Clues, picture: &mut Picture) -> Option<()> {
+    let (width, height) = (picture.width(), picture.height());
+
+    let mut queue: VecDeque<Lane> = (0..height)
+        .map(Lane::Row)
+        .chain((0..width).map(Lane::Column))
+        .collect();
+    let mut queued_rows = vec![true; height];
+    let mut queued_columns = vec![true; width];
+
+    while let Some(lane) = queue.pop_front() {
+        let (positions, groups): (Vec<Pos>, _) = match lane {
+            Lane::Row(y) => {
+                queued_rows[y] = false;
+                ((0..width).map(|x| (x, y)).collect(), &clues.rows[y])
+            }
+            Lane::Column(x) => {
+                queued_columns[x] = false;
+                ((0..height).map(|y| (x, y)).collect(), &clues.columns[x])
+            }
+        };
+
+        let line = Line {
+            modes: positions.iter().map(|&pos| picture[pos]).collect(),
+            groups: groups.clone(),
+        };
+        let settled = day12::settle(&line)?;
+
+        // Lanes crossing changed cells may settle further
+        for (&(x, y), mode) in positions.iter().zip(settled) {
+            if picture[(x, y)] == mode {
+                continue;
+            }
+            picture[(x, y)] = mode;
+            match lane {
+                Lane::Row(_) if !queued_columns[x] => {
+                    queued_columns[x] = true;
+                    queue.push_back(Lane::Column(x));
+                }
+                Lane::Column(_) if !queued_rows[y] => {
+                    queued_rows[y] = true;
+                    queue.push_back(Lane::Row(y));
+                }
+                _ => (),
+            }
+        }
+    }
+
+    Some(())
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use aoc_2023::solution::{self, Part, Runner};
+use aoc_2023::utils::Parts;
+use serde::Serialize;
+
+/// Result of a single part of a single input, flattened for machine consumption
+#[derive(Debug, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub input: String,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub status: &'static str,
+    pub error: Option<String>,
+    /// Time spent parsing the input, missing if reading or parsing failed
+    pub parse_ns: Option<u64>,
+    /// Time spent solving the part, missing if reading or parsing failed
+    pub time_ns: Option<u64>,
+}
+
+impl Record {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+fn nanos(time: Duration) -> u64 {
+    u64::try_from(time.as_nanos()).unwrap_or(u64::MAX)
+}
+
+fn part_number(part: Part) -> u8 {
+    match part {
+        Part::Part1 => 1,
+        Part::Part2 => 2,
+    }
+}
+
+/// Solve `file` and return a record for every selected part
+///
+/// Failing to read or parse the input fails every selected part.
+pub fn solve_file(runner: &dyn Runner, file: &str, parts: Parts) -> Vec<Record> {
+    let record = |part, answer: Result<String, String>, times: Option<(Duration, Duration)>| {
+        let (answer, error) = match answer {
+            Ok(answer) => (Some(answer), None),
+            Err(e) => (None, Some(e)),
+        };
+        Record {
+            day: runner.day(),
+            input: file.to_owned(),
+            part: part_number(part),
+            answer,
+            status: if error.is_none() { "ok" } else { "error" },
+            error,
+            parse_ns: times.map(|(parse_time, _)| nanos(parse_time)),
+            time_ns: times.map(|(_, time)| nanos(time)),
+        }
+    };
+
+    match solution::solve_file(runner, file, parts) {
+        Ok(result) => result
+            .parts
+            .into_iter()
+            .map(|part| {
+                let answer = part.answer.map_err(|e| e.to_string());
+                record(part.part, answer, Some((result.parse_time, part.time)))
+            })
+            .collect(),
+        Err(e) => [(parts.part1, Part::Part1), (parts.part2, Part::Part2)]
+            .into_iter()
+            .filter(|(selected, _)| *selected)
+            .map(|(_, part)| record(part, Err(e.to_string()), None))
+            .collect(),
+    }
+}
+
+pub fn print_json(records: &[Record]) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(records)?);
+    Ok(())
+}
+
+pub fn print_csv(records: &[Record]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(std::io::stdout());
+    for record in records {
+        writer.serialize(record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
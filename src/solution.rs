@@ -0,0 +1,126 @@
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+use crate::utils::{self, Parts};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    Part1,
+    Part2,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::Part1 => write!(f, "Part1"),
+            Part::Part2 => write!(f, "Part2"),
+        }
+    }
+}
+
+/// Solution of a single day
+///
+/// Input is parsed once and then shared by both parts, so it holds whatever
+/// either part needs to read the input its own way.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+    type Answer: Display;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error>;
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Error>;
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Error>;
+
+    /// Caveats about the answer of `part` worth reporting, like a fallback used to find it
+    fn warnings(&self, _input: &Self::Input, _part: Part) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct PartResult {
+    pub part: Part,
+    pub answer: Result<String, Error>,
+    /// Time spent solving the part, excluding parsing
+    pub time: Duration,
+    pub warnings: Vec<String>,
+}
+
+/// Results of all selected parts of a single input
+#[derive(Debug)]
+pub struct RunResult {
+    /// Time spent parsing the input shared by both parts
+    pub parse_time: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+/// Type-erased `Solution`, so days with different input and answer types
+/// can be kept in a single registry
+pub trait Runner {
+    fn day(&self) -> u8;
+
+    fn run(&self, input: &str, parts: Parts) -> Result<RunResult, Error>;
+}
+
+impl<S: Solution> Runner for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn run(&self, input: &str, parts: Parts) -> Result<RunResult, Error> {
+        let start = Instant::now();
+        let input = self.parse(input)?;
+        let parse_time = start.elapsed();
+
+        let mut results = Vec::new();
+        if parts.part1 {
+            let start = Instant::now();
+            let answer = self.part1(&input).map(|answer| answer.to_string());
+            let time = start.elapsed();
+            results.push(PartResult {
+                part: Part::Part1,
+                answer,
+                time,
+                warnings: self.warnings(&input, Part::Part1),
+            });
+        }
+        if parts.part2 {
+            let start = Instant::now();
+            let answer = self.part2(&input).map(|answer| answer.to_string());
+            let time = start.elapsed();
+            results.push(PartResult {
+                part: Part::Part2,
+                answer,
+                time,
+                warnings: self.warnings(&input, Part::Part2),
+            });
+        }
+
+        Ok(RunResult {
+            parse_time,
+            parts: results,
+        })
+    }
+}
+
+/// Read input `file` and solve selected parts, attaching the file name to parse errors
+pub fn solve_file(runner: &dyn Runner, file: &str, parts: Parts) -> Result<RunResult, Error> {
+    let input = utils::read_input(file)?;
+    let file = utils::input_label(file);
+    let mut result = runner.run(&input, parts).map_err(|e| e.with_file(file))?;
+
+    result.parts = result
+        .parts
+        .into_iter()
+        .map(|part| PartResult {
+            answer: part.answer.map_err(|e| e.with_file(file)),
+            ..part
+        })
+        .collect();
+
+    Ok(result)
+}
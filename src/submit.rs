@@ -0,0 +1,296 @@
+use std::fmt::{self, Display};
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aoc_2023::solution::{self, Runner};
+use aoc_2023::utils::Parts;
+use regex::Regex;
+
+use crate::cli::SubmitArgs;
+use crate::client::Client;
+use crate::config::Config;
+use crate::fetch;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Answer was submitted too soon after the previous one
+    Wait,
+    /// Part was already solved before
+    Solved,
+    Unknown,
+}
+
+impl Verdict {
+    fn is_rejected(self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Right => write!(f, "right"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "too-high"),
+            Verdict::TooLow => write!(f, "too-low"),
+            Verdict::Wait => write!(f, "wait"),
+            Verdict::Solved => write!(f, "solved"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "right" => Ok(Verdict::Right),
+            "wrong" => Ok(Verdict::Wrong),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "wait" => Ok(Verdict::Wait),
+            "solved" => Ok(Verdict::Solved),
+            "unknown" => Ok(Verdict::Unknown),
+            _ => Err(format!("Invalid verdict: {}", s)),
+        }
+    }
+}
+
+/// Single submitted answer
+struct Attempt {
+    time: u64,
+    day: u8,
+    part: u8,
+    verdict: Verdict,
+    answer: String,
+}
+
+/// Parse history file
+///
+/// Each non-empty line not starting with `#` contains unix time of the
+/// attempt, day, part, verdict and the submitted answer separated by whitespace.
+fn parse_history(input: &str) -> Result<Vec<Attempt>, Box<dyn std::error::Error>> {
+    let mut history = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let invalid = || format!("Invalid history line {}: {}", i + 1, line);
+        let [time, day, part, verdict, answer] = fields[..] else {
+            return Err(invalid().into());
+        };
+
+        history.push(Attempt {
+            time: time.parse().map_err(|_| invalid())?,
+            day: day.parse().map_err(|_| invalid())?,
+            part: part.parse().map_err(|_| invalid())?,
+            verdict: verdict.parse().map_err(|_| invalid())?,
+            answer: answer.to_owned(),
+        });
+    }
+
+    Ok(history)
+}
+
+fn record_attempt(file: &Path, attempt: &Attempt) -> Result<(), Box<dyn std::error::Error>> {
+    let new = !file.exists();
+    let mut history = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file)?;
+    if new {
+        writeln!(history, "# time day part verdict answer")?;
+    }
+    writeln!(
+        history,
+        "{} {} {} {} {}",
+        attempt.time, attempt.day, attempt.part, attempt.verdict, attempt.answer
+    )?;
+    Ok(())
+}
+
+/// Find verdict and message in the response page
+fn parse_response(page: &str) -> (Verdict, String) {
+    let article = Regex::new(r"(?s)<article>(.*?)</article>").unwrap();
+    let tag = Regex::new(r"<[^>]*>").unwrap();
+    let whitespace = Regex::new(r"\s+").unwrap();
+
+    let message = article
+        .captures(page)
+        .map_or(page, |captures| captures.get(1).unwrap().as_str());
+    let message = tag.replace_all(message, "");
+    let message = whitespace.replace_all(message.trim(), " ").into_owned();
+
+    let verdict = if message.contains("That's the right answer") {
+        Verdict::Right
+    } else if message.contains("too high") {
+        Verdict::TooHigh
+    } else if message.contains("too low") {
+        Verdict::TooLow
+    } else if message.contains("not the right answer") {
+        Verdict::Wrong
+    } else if message.contains("gave an answer too recently") {
+        Verdict::Wait
+    } else if message.contains("Did you already complete it")
+        || message.contains("don't seem to be solving the right level")
+    {
+        Verdict::Solved
+    } else {
+        Verdict::Unknown
+    };
+
+    (verdict, message)
+}
+
+/// Check `answer` against earlier attempts
+///
+/// Returns an error if the answer shouldn't be submitted at all, otherwise
+/// warnings about bounds reported by earlier attempts the answer doesn't fit in.
+fn check_answer(
+    history: &[Attempt],
+    day: u8,
+    part: u8,
+    answer: &str,
+) -> Result<Vec<String>, String> {
+    let attempts = history
+        .iter()
+        .filter(|attempt| attempt.day == day && attempt.part == part)
+        .collect::<Vec<_>>();
+
+    if let Some(right) = attempts.iter().find(|a| a.verdict == Verdict::Right) {
+        return Err(format!("Already solved with answer {}", right.answer));
+    }
+    if let Some(rejected) = attempts
+        .iter()
+        .find(|a| a.answer == answer && a.verdict.is_rejected())
+    {
+        return Err(format!(
+            "Answer {} was already rejected ({})",
+            answer, rejected.verdict
+        ));
+    }
+
+    let mut warnings = Vec::new();
+    let Ok(value) = answer.parse::<i128>() else {
+        return Ok(warnings);
+    };
+    let bound = |verdict| {
+        attempts
+            .iter()
+            .filter(move |a| a.verdict == verdict)
+            .filter_map(|a| a.answer.parse::<i128>().ok())
+    };
+
+    if let Some(high) = bound(Verdict::TooHigh).min() {
+        if value >= high {
+            warnings.push(format!("{} was too high, answer must be lower", high));
+        }
+    }
+    if let Some(low) = bound(Verdict::TooLow).max() {
+        if value <= low {
+            warnings.push(format!("{} was too low, answer must be higher", low));
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Solve part `part` of the given or downloaded input of `day`
+fn solve(
+    registry: &[Box<dyn Runner>],
+    input_dir: &Path,
+    args: &SubmitArgs,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let runner = registry
+        .iter()
+        .find(|runner| runner.day() == args.day)
+        .ok_or(format!("Day {} is not implemented", args.day))?;
+
+    let file = match &args.input {
+        Some(file) => file.clone(),
+        None => fetch::input_path(input_dir, args.day)
+            .to_str()
+            .ok_or("Invalid input directory")?
+            .to_owned(),
+    };
+    let parts = match args.part {
+        1 => Parts::ONLY_PART1,
+        _ => Parts::ONLY_PART2,
+    };
+
+    let result = solution::solve_file(runner.as_ref(), &file, parts)?;
+    let part = result
+        .parts
+        .into_iter()
+        .next()
+        .ok_or("Part was not solved")?;
+    Ok(part.answer?)
+}
+
+/// Submit answer of a single part, refusing answers known to be wrong
+///
+/// Returns `true` if the answer was accepted.
+pub fn submit(
+    registry: &[Box<dyn Runner>],
+    config: &Config,
+    input_dir: &Path,
+    args: &SubmitArgs,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let answer = match &args.answer {
+        Some(answer) => answer.trim().to_owned(),
+        None => solve(registry, input_dir, args)?,
+    };
+    if answer.is_empty() || answer.contains(char::is_whitespace) {
+        return Err(format!("Invalid answer: {:?}", answer).into());
+    }
+
+    let history_file = match &args.history {
+        Some(file) => file.clone(),
+        None => input_dir.join("history.txt"),
+    };
+    let history = match std::fs::read_to_string(&history_file) {
+        Ok(history) => parse_history(&history)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    println!("DAY{:02} Part{}: {}", args.day, args.part, answer);
+
+    let warnings = check_answer(&history, args.day, args.part, &answer)?;
+    for warning in &warnings {
+        println!("Warning: {}", warning);
+    }
+    if !warnings.is_empty() && !args.force {
+        return Err("Answer not submitted, use --force to submit it anyway".into());
+    }
+
+    let client = Client::new(config, input_dir.join(fetch::STATE_FILE))?;
+    let page = client.answer(args.day, args.part, &answer)?;
+    let (verdict, message) = parse_response(&page);
+
+    record_attempt(
+        &history_file,
+        &Attempt {
+            time: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            day: args.day,
+            part: args.part,
+            verdict,
+            answer,
+        },
+    )?;
+
+    println!("{}: {}", verdict, message);
+
+    Ok(verdict == Verdict::Right)
+}
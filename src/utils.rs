@@ -1,22 +1,131 @@
 use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
 
-const INPUT_DIR: &str = "inputs";
+use crate::error::Error;
 
-pub fn input_files(prefix: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    // Find all files in the input directory that start with the prefix
-    let mut files = Vec::new();
+pub mod cycle;
+pub mod grid;
+pub mod interval;
+pub mod range_map;
+pub mod search;
+
+pub use grid::Grid;
+
+/// Directory searched for `dayNN*` inputs unless overridden
+pub const DEFAULT_INPUT_DIR: &str = "inputs";
+
+/// Environment variable overriding `DEFAULT_INPUT_DIR`
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+/// Input name that makes `read_input` read from stdin instead of a file
+pub const STDIN_INPUT: &str = "-";
+
+static STDIN_BUFFER: OnceLock<String> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Parts {
+    pub part1: bool,
+    pub part2: bool,
+}
 
-    let full_prefix = format!("{}/{}", INPUT_DIR, prefix);
-    let full_prefix = full_prefix.as_str();
+impl Parts {
+    pub const BOTH: Parts = Parts {
+        part1: true,
+        part2: true,
+    };
+    pub const ONLY_PART1: Parts = Parts {
+        part1: true,
+        part2: false,
+    };
+    pub const ONLY_PART2: Parts = Parts {
+        part1: false,
+        part2: true,
+    };
+}
+
+/// Files in `dir` whose name starts with `prefix`, sorted by name
+pub fn input_files(dir: &Path, prefix: &str) -> Result<Vec<String>, Error> {
+    let mut files = Vec::new();
 
-    let paths = fs::read_dir(INPUT_DIR)?;
-    for path in paths {
-        let path = path?.path();
-        let path_str = path.to_str().ok_or("Internal error: Invalid path")?;
-        if path_str.starts_with(full_prefix) {
-            files.push(path_str.to_owned());
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            // Non UTF-8 names can't match the prefix
+            continue;
+        };
+        if !name.starts_with(prefix) || !path.is_file() {
+            continue;
         }
+        let Some(path_str) = path.to_str() else {
+            continue;
+        };
+        files.push(path_str.to_owned());
     }
     files.sort();
     Ok(files)
 }
+
+/// Expand glob patterns among explicitly given inputs
+///
+/// Plain paths and `STDIN_INPUT` are kept as they are, matches of each
+/// pattern are sorted by path. Inputs are returned in the order they were
+/// given, without duplicates. A pattern without any match is an error.
+pub fn expand_inputs(inputs: &[String]) -> Result<Vec<String>, Error> {
+    let mut files: Vec<String> = Vec::new();
+
+    for input in inputs {
+        if input == STDIN_INPUT || !input.contains(['*', '?', '[']) {
+            if !files.contains(input) {
+                files.push(input.clone());
+            }
+            continue;
+        }
+
+        let mut matches = Vec::new();
+        for path in glob::glob(input)? {
+            let path = path.map_err(std::io::Error::from)?;
+            if let Some(path) = path.to_str() {
+                matches.push(path.to_owned());
+            }
+        }
+        if matches.is_empty() {
+            return Err(Error::NoInputs(input.clone()));
+        }
+
+        matches.sort();
+        for path in matches {
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Name of the input shown to the user
+pub fn input_label(file: &str) -> &str {
+    if file == STDIN_INPUT {
+        "<stdin>"
+    } else {
+        file
+    }
+}
+
+pub fn read_input(file: &str) -> Result<String, Error> {
+    if file != STDIN_INPUT {
+        return Ok(fs::read_to_string(file)?);
+    }
+
+    // Stdin can only be consumed once, but the same input may be
+    // solved by multiple days - keep it around for subsequent reads
+    if let Some(input) = STDIN_BUFFER.get() {
+        return Ok(input.clone());
+    }
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    Ok(STDIN_BUFFER.get_or_init(|| input).clone())
+}
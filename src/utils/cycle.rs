@@ -0,0 +1,135 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Cycle of a sequence of states `x0, step(x0), step(step(x0)), ...`
+///
+/// States from index `start` on repeat every `length` steps.
+/// Sequences of states from a finite set always end up in a cycle,
+/// functions finding it never return for sequences that don't.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Smallest index of a state equal to the state at index `n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// State after `n` steps from `initial`
+pub fn nth<S: Clone>(initial: &S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let mut state = initial.clone();
+    for _ in 0..n {
+        state = step(&state);
+    }
+    state
+}
+
+/// State after `n` steps from `initial`, taking a shortcut through the cycle
+///
+/// Works for huge `n` as long as the cycle is short, using Brent's algorithm
+/// so only a few states are kept in memory.
+pub fn state_after<S: Clone + Eq>(initial: &S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let cycle = brent(initial, &mut step);
+    nth(initial, step, cycle.reduce(n))
+}
+
+fn hash_state<S: Hash>(state: &S) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Find cycle by remembering hashes of all visited states
+///
+/// Needs a single pass over the sequence, but keeps a hash of every state.
+/// States are only regenerated to rule out hash collisions.
+pub fn find_cycle<S: Clone + Eq + Hash>(initial: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+    let mut state = initial.clone();
+    let mut index = 0;
+
+    loop {
+        let hash = hash_state(&state);
+        for &previous in seen.get(&hash).into_iter().flatten() {
+            if nth(initial, &mut step, previous) == state {
+                return Cycle {
+                    start: previous,
+                    length: index - previous,
+                };
+            }
+        }
+
+        seen.entry(hash).or_default().push(index);
+        state = step(&state);
+        index += 1;
+    }
+}
+
+/// Find cycle using Brent's algorithm, keeping only two states in memory
+pub fn brent<S: Clone + Eq>(initial: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Find cycle length by moving tortoise to the hare at every power of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Hare one cycle ahead of tortoise meets it at the start of the cycle
+    let mut tortoise = initial.clone();
+    let mut hare = nth(initial, &mut step, length);
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Find cycle using Floyd's algorithm, keeping only two states in memory
+pub fn floyd<S: Clone + Eq>(initial: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Hare moving twice as fast meets tortoise somewhere in the cycle
+    let mut tortoise = step(initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // Distance to the meeting point is a multiple of the cycle length
+    let mut tortoise = initial.clone();
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
@@ -0,0 +1,247 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+use crate::error::ParseError;
+
+/// Position `(x, y)` in a grid, `(0, 0)` is the top left corner
+pub type Pos = (usize, usize);
+
+/// Offset `(dx, dy)` between two positions
+pub type Dir = (isize, isize);
+
+pub const UP: Dir = (0, -1);
+pub const DOWN: Dir = (0, 1);
+pub const LEFT: Dir = (-1, 0);
+pub const RIGHT: Dir = (1, 0);
+
+/// Directions to 4-connected neighbours
+pub const DIRECTIONS4: [Dir; 4] = [UP, DOWN, LEFT, RIGHT];
+
+/// Directions to 8-connected neighbours
+pub const DIRECTIONS8: [Dir; 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Number of 4-connected steps between two positions
+pub fn manhattan(a: Pos, b: Pos) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+/// Rectangular 2D grid stored row by row
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid from cells listed row by row
+    ///
+    /// Panics if the number of cells doesn't match the size.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "Invalid number of cells");
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(width, height, vec![value; width * height])
+    }
+
+    /// Parse grid with a cell per character, line by line
+    ///
+    /// Line numbers in errors are relative to `input`.
+    pub fn parse(
+        input: &str,
+        mut parse_cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for (y, line) in input.lines().enumerate() {
+            let mut row_width = 0;
+            for (x, c) in line.chars().enumerate() {
+                let Some(cell) = parse_cell(c) else {
+                    let mut error = ParseError::new(y + 1, &c.to_string(), "Invalid cell");
+                    error.column = Some(x + 1);
+                    return Err(error);
+                };
+                cells.push(cell);
+                row_width += 1;
+            }
+
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(ParseError::new(y + 1, line, "Rows have different lengths"));
+            }
+            height += 1;
+        }
+
+        Ok(Self::new(width.unwrap_or(0), height, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.1 * self.width + pos.0])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.1 * self.width + pos.0])
+        } else {
+            None
+        }
+    }
+
+    /// Position `dir` away from `pos`, `None` if it lies outside of the grid
+    pub fn offset(&self, (x, y): Pos, (dx, dy): Dir) -> Option<Pos> {
+        let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Up, down, left and right neighbours of `pos` that lie inside the grid
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRECTIONS4
+            .iter()
+            .filter_map(move |&dir| self.offset(pos, dir))
+    }
+
+    /// Neighbours of `pos` including diagonal ones that lie inside the grid
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRECTIONS8
+            .iter()
+            .filter_map(move |&dir| self.offset(pos, dir))
+    }
+
+    /// All positions row by row
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// All cells with their positions row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Position of the first cell matching `predicate`, row by row
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "Column out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// Build grid of given size by computing every cell from its position
+    fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+        Self::new(width, height, cells)
+    }
+
+    /// Swap rows with columns
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.height, self.width, |(x, y)| self[(y, x)].clone())
+    }
+
+    /// Rotate by 90 degrees clockwise
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        Self::from_fn(self.height, self.width, |(x, y)| {
+            self[(y, height - 1 - x)].clone()
+        })
+    }
+
+    /// Rotate by 90 degrees counter-clockwise
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        Self::from_fn(self.height, self.width, |(x, y)| {
+            self[(width - 1 - y, x)].clone()
+        })
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).expect("Position out of bounds")
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos).expect("Position out of bounds")
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
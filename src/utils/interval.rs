@@ -0,0 +1,190 @@
+use std::ops::Range;
+
+/// Set of `u64` values stored as sorted ranges
+///
+/// Ranges never overlap or touch each other, they are coalesced on insertion.
+/// Empty ranges are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<Range<u64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of values in the set
+    pub fn len(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|range| range.end - range.start)
+            .sum()
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.contains(&value))
+    }
+
+    /// Lowest value of the set
+    pub fn min(&self) -> Option<u64> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    /// Highest value of the set
+    pub fn max(&self) -> Option<u64> {
+        self.ranges.last().map(|range| range.end - 1)
+    }
+
+    pub fn insert(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // Ranges overlapping or touching the new one are merged into it
+        let from = self.ranges.partition_point(|r| r.end < range.start);
+        let to = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if from < to {
+            self.ranges[from].start.min(range.start)..self.ranges[to - 1].end.max(range.end)
+        } else {
+            range
+        };
+        self.ranges.splice(from..to, [merged]);
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        for range in &other.ranges {
+            result.insert(range.clone());
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            // Range ending first can't overlap anything else
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Values of this set not contained in `other`
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+
+        for range in &self.ranges {
+            let mut start = range.start;
+            // Skip ranges of `other` ending before this one
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                let cut = &other.ranges[k];
+                if cut.start > start {
+                    ranges.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+                k += 1;
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+
+        Self { ranges }
+    }
+}
+
+impl From<Range<u64>> for IntervalSet {
+    fn from(range: Range<u64>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl FromIterator<Range<u64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<u64>>>(iter: I) -> Self {
+        let mut ranges: Vec<Range<u64>> = iter.into_iter().filter(|r| !r.is_empty()).collect();
+        ranges.sort_by_key(|range| range.start);
+
+        // Coalesce overlapping and touching ranges
+        let mut coalesced: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match coalesced.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => coalesced.push(range),
+            }
+        }
+
+        Self { ranges: coalesced }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_coalesces_overlapping_and_touching_ranges() {
+        let mut set = IntervalSet::new();
+        set.insert(10..20);
+        set.insert(30..40);
+        set.insert(20..25);
+        set.insert(5..5);
+        assert_eq!(set.ranges(), &[10..25, 30..40]);
+
+        set.insert(24..31);
+        assert_eq!(set, IntervalSet::from(10..40));
+        assert_eq!(set.len(), 30);
+        assert_eq!((set.min(), set.max()), (Some(10), Some(39)));
+    }
+
+    #[test]
+    fn from_iter_sorts_and_coalesces() {
+        let set: IntervalSet = [(30..40), (0..0), (5..10), (8..12)].into_iter().collect();
+        assert_eq!(set.ranges(), &[5..12, 30..40]);
+        assert!(set.contains(11));
+        assert!(!set.contains(12));
+        assert!(!set.contains(4));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: IntervalSet = [0..10, 20..30].into_iter().collect();
+        let b: IntervalSet = [5..25, 28..35].into_iter().collect();
+
+        assert_eq!(a.union(&b), IntervalSet::from(0..35));
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25, 28..30]);
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 25..28]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20, 30..35]);
+        assert!(a.intersection(&IntervalSet::new()).is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::utils::interval::IntervalSet;
+
+/// End of the domain, exclusive so that it's still a valid `u64`
+const DOMAIN_END: u128 = u64::MAX as u128;
+
+/// Values `source..source + length` map to `destination..destination + length`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    pub source: u64,
+    pub destination: u64,
+    pub length: u64,
+}
+
+impl Mapping {
+    fn shift(&self) -> i128 {
+        self.destination as i128 - self.source as i128
+    }
+}
+
+/// Piece `start..end` of the domain whose values are shifted by `shift`
+type Piece = (u128, u128, i128);
+
+/// Piecewise-linear map of `u64` values
+///
+/// Values not covered by any mapping map to themselves. Values mapped
+/// past `u64::MAX - 1` fall out of the domain and have no image.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeMap {
+    /// Sorted by source, where mappings overlap the first one wins
+    mappings: Vec<Mapping>,
+}
+
+impl RangeMap {
+    pub fn new(mut mappings: Vec<Mapping>) -> Self {
+        mappings.retain(|mapping| mapping.length > 0);
+        mappings.sort_by_key(|mapping| mapping.source);
+        Self { mappings }
+    }
+
+    /// Map leaving every value as it is
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.mappings.iter().all(|mapping| mapping.shift() == 0)
+    }
+
+    /// Pieces covering the whole domain, including identity gaps between mappings
+    fn pieces(&self) -> Vec<Piece> {
+        let mut pieces = Vec::new();
+        let mut position = 0;
+
+        for mapping in &self.mappings {
+            let start = (mapping.source as u128).max(position);
+            let end = (mapping.source as u128 + mapping.length as u128).min(DOMAIN_END);
+            if start >= end {
+                // Hidden by previous mappings
+                continue;
+            }
+            if position < start {
+                pieces.push((position, start, 0));
+            }
+            pieces.push((start, end, mapping.shift()));
+            position = end;
+        }
+        if position < DOMAIN_END {
+            pieces.push((position, DOMAIN_END, 0));
+        }
+
+        pieces
+    }
+
+    /// Map from sorted pieces, merging neighbours with the same shift
+    fn from_pieces(pieces: impl IntoIterator<Item = Piece>) -> Self {
+        let mut mappings: Vec<Mapping> = Vec::new();
+
+        for (start, end, shift) in pieces {
+            if shift == 0 {
+                continue;
+            }
+            if let Some(last) = mappings.last_mut() {
+                if last.source as u128 + last.length as u128 == start && last.shift() == shift {
+                    last.length += (end - start) as u64;
+                    continue;
+                }
+            }
+            mappings.push(Mapping {
+                source: start as u64,
+                destination: (start as i128 + shift) as u64,
+                length: (end - start) as u64,
+            });
+        }
+
+        Self { mappings }
+    }
+
+    /// Image of `value`, `None` if it falls out of the domain
+    pub fn get(&self, value: u64) -> Option<u64> {
+        let (_, _, shift) = self
+            .pieces()
+            .into_iter()
+            .find(|&(_, end, _)| (value as u128) < end)?;
+        u64::try_from(value as i128 + shift).ok()
+    }
+
+    /// Images of all values of the set
+    pub fn image(&self, set: &IntervalSet) -> IntervalSet {
+        let pieces = self.pieces();
+
+        set.ranges()
+            .iter()
+            .flat_map(|range| {
+                pieces
+                    .iter()
+                    .filter_map(|&(piece_start, piece_end, shift)| {
+                        let from = (range.start as i128).max(piece_start as i128) + shift;
+                        let to = (range.end as i128).min(piece_end as i128) + shift;
+                        let to = to.min(DOMAIN_END as i128);
+                        (from < to).then_some(from as u64..to as u64)
+                    })
+            })
+            .collect()
+    }
+
+    /// All values mapped into the set
+    pub fn preimage(&self, set: &IntervalSet) -> IntervalSet {
+        let pieces = self.pieces();
+
+        set.ranges()
+            .iter()
+            .flat_map(|range| {
+                pieces
+                    .iter()
+                    .filter_map(|&(piece_start, piece_end, shift)| {
+                        let from = (range.start as i128 - shift).max(piece_start as i128);
+                        let to = (range.end as i128 - shift).min(piece_end as i128);
+                        (from < to).then_some(from as u64..to as u64)
+                    })
+            })
+            .collect()
+    }
+
+    /// Single map applying this map and then `next`
+    ///
+    /// Values this map moves out of the domain keep their value.
+    pub fn compose(&self, next: &RangeMap) -> RangeMap {
+        let next_pieces = next.pieces();
+
+        let pieces = self.pieces().into_iter().flat_map(|(start, end, shift)| {
+            let image_start = start as i128 + shift;
+            let image_end = end as i128 + shift;
+            next_pieces
+                .iter()
+                .filter_map(move |&(next_start, next_end, next_shift)| {
+                    let from = image_start.max(next_start as i128);
+                    let to = image_end.min(next_end as i128);
+                    (from < to).then(|| {
+                        (
+                            (from - shift) as u128,
+                            (to - shift) as u128,
+                            shift + next_shift,
+                        )
+                    })
+                })
+        });
+
+        Self::from_pieces(pieces)
+    }
+
+    /// Map undoing this one, `None` if some values share an image
+    pub fn invert(&self) -> Option<RangeMap> {
+        let inverse = Self::new(
+            self.mappings
+                .iter()
+                .map(|mapping| Mapping {
+                    source: mapping.destination,
+                    destination: mapping.source,
+                    length: mapping.length,
+                })
+                .collect(),
+        );
+
+        (self.compose(&inverse).is_identity() && inverse.compose(self).is_identity())
+            .then_some(inverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(destination: u64, source: u64, length: u64) -> Mapping {
+        Mapping {
+            source,
+            destination,
+            length,
+        }
+    }
+
+    /// Seed-to-soil map of the day 5 example
+    fn seed_to_soil() -> RangeMap {
+        RangeMap::new(vec![mapping(50, 98, 2), mapping(52, 50, 48)])
+    }
+
+    #[test]
+    fn get_maps_covered_values_and_keeps_the_rest() {
+        let map = seed_to_soil();
+        assert_eq!(map.get(79), Some(81));
+        assert_eq!(map.get(14), Some(14));
+        assert_eq!(map.get(98), Some(50));
+        assert_eq!(map.get(100), Some(100));
+    }
+
+    #[test]
+    fn image_and_preimage_of_ranges() {
+        let map = seed_to_soil();
+        let seeds: IntervalSet = [79..93, 55..68].into_iter().collect();
+        assert_eq!(map.image(&seeds).ranges(), &[57..70, 81..95]);
+    }
+
+    #[test]
+    fn values_mapped_past_the_domain_have_no_image() {
+        let map = RangeMap::new(vec![mapping(u64::MAX - 5, 0, 10)]);
+        assert_eq!(map.get(2), Some(u64::MAX - 3));
+        assert_eq!(map.get(7), None);
+        assert_eq!(
+            map.image(&IntervalSet::from(0..10)),
+            IntervalSet::from(u64::MAX - 5..u64::MAX)
+        );
+    }
+
+    #[test]
+    fn compose_applies_both_maps() {
+        let first = seed_to_soil();
+        let second = RangeMap::new(vec![mapping(0, 15, 37), mapping(37, 52, 2)]);
+        let composed = first.compose(&second);
+
+        for value in 0..120 {
+            let expected = first.get(value).and_then(|value| second.get(value));
+            assert_eq!(composed.get(value), expected, "value {}", value);
+        }
+        assert!(RangeMap::identity().compose(&first).eq(&first));
+    }
+
+    #[test]
+    fn invert_only_bijections() {
+        let map = seed_to_soil();
+        let inverse = map.invert().unwrap();
+        for value in 0..120 {
+            assert_eq!(inverse.get(map.get(value).unwrap()), Some(value));
+        }
+
+        // 0..5 and 10..15 both map to 10..15
+        let merging = RangeMap::new(vec![mapping(10, 0, 5)]);
+        assert_eq!(merging.invert(), None);
+    }
+}
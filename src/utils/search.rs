@@ -0,0 +1,298 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Costs of all reached nodes and the parents they were reached from
+#[derive(Clone, Debug)]
+pub struct Search<N, C> {
+    pub costs: HashMap<N, C>,
+    pub parents: HashMap<N, N>,
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> Search<N, C> {
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    /// Path from a start node to `node`, both included
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        self.costs
+            .contains_key(node)
+            .then(|| reconstruct_path(&self.parents, node.clone()))
+    }
+}
+
+/// Follow `parents` back from `node` to a node without parent
+///
+/// Returned path starts with that node and ends with `node`.
+pub fn reconstruct_path<N: Eq + Hash + Clone>(parents: &HashMap<N, N>, node: N) -> Vec<N> {
+    let mut path = vec![node];
+    while let Some(parent) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Breadth-first search, cost of a node is the number of steps from the nearest start
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> Search<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut costs = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if !costs.contains_key(&start) {
+            costs.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        let cost = costs[&node] + 1;
+        for next in neighbours(&node) {
+            if costs.contains_key(&next) {
+                continue;
+            }
+            costs.insert(next.clone(), cost);
+            parents.insert(next.clone(), node.clone());
+            queue.push_back(next);
+        }
+    }
+
+    Search { costs, parents }
+}
+
+/// Shortest paths from the nearest start node to every reachable node
+///
+/// `neighbours` returns nodes reachable from a node together with costs of the edges.
+/// Costs must not be negative, `C::default()` is the zero cost.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (search, _) = best_first(starts, neighbours, |_| C::default(), |_| false);
+    search
+}
+
+/// Shortest path from `start` to the first node satisfying `is_goal`
+///
+/// `heuristic` estimates cost of the rest of the path and must never overestimate it
+/// (e.g. `grid::manhattan` for unit steps on a grid). Nodes reached more cheaply after
+/// they were expanded are expanded again, so the heuristic doesn't need to be consistent.
+/// Returns the path including both ends and its cost.
+pub fn astar<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (search, goal) = best_first([start], neighbours, heuristic, is_goal);
+    let goal = goal?;
+    Some((search.path(&goal)?, search.cost(&goal)?))
+}
+
+/// Dijkstra ordered by cost plus heuristic, stopping at the first goal
+///
+/// Nodes are expanded again whenever a cheaper path to them is found.
+fn best_first<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> (Search<N, C>, Option<N>)
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut costs = HashMap::new();
+    let mut parents = HashMap::new();
+
+    // Nodes are kept aside with costs they were queued with, so they don't
+    // need to be `Ord`, the heap only orders their indices
+    let mut nodes = Vec::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if costs.contains_key(&start) {
+            continue;
+        }
+        costs.insert(start.clone(), C::default());
+        heap.push(Reverse((heuristic(&start), nodes.len())));
+        nodes.push((start, C::default()));
+    }
+
+    while let Some(Reverse((_, index))) = heap.pop() {
+        let (node, cost) = nodes[index].clone();
+        if costs[&node] < cost {
+            // Already reached more cheaply since it was queued
+            continue;
+        }
+        if is_goal(&node) {
+            return (Search { costs, parents }, Some(node));
+        }
+
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+            costs.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), node.clone());
+            heap.push(Reverse((next_cost + heuristic(&next), nodes.len())));
+            nodes.push((next, next_cost));
+        }
+    }
+
+    (Search { costs, parents }, None)
+}
+
+/// All nodes reachable from any of `starts`, including them
+pub fn flood_fill<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> HashSet<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = HashSet::new();
+    let mut stack = Vec::new();
+
+    for start in starts {
+        if visited.insert(start.clone()) {
+            stack.push(start);
+        }
+    }
+
+    while let Some(node) = stack.pop() {
+        for next in neighbours(&node) {
+            if visited.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+
+    visited
+}
+
+/// Split `nodes` into groups of nodes reachable from each other
+///
+/// `neighbours` is expected to be symmetric. Nodes reachable from `nodes`
+/// but not listed in them are included in the components as well.
+pub fn connected_components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> Vec<HashSet<N>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut components: Vec<HashSet<N>> = Vec::new();
+
+    for node in nodes {
+        if components.iter().any(|component| component.contains(&node)) {
+            continue;
+        }
+        components.push(flood_fill([node], &mut neighbours));
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Directed graph as `(from, to, cost)` edges
+    fn edges<'a>(graph: &'a [(char, char, u32)]) -> impl FnMut(&char) -> Vec<(char, u32)> + 'a {
+        move |&node| {
+            graph
+                .iter()
+                .filter(|&&(from, _, _)| from == node)
+                .map(|&(_, to, cost)| (to, cost))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn dijkstra_finds_cheapest_paths() {
+        let graph = [
+            ('S', 'A', 4),
+            ('S', 'B', 1),
+            ('B', 'A', 2),
+            ('A', 'C', 1),
+            ('B', 'C', 5),
+        ];
+        let search = dijkstra(['S'], edges(&graph));
+
+        assert_eq!(search.cost(&'A'), Some(3));
+        assert_eq!(search.cost(&'C'), Some(4));
+        assert_eq!(search.path(&'C'), Some(vec!['S', 'B', 'A', 'C']));
+        assert_eq!(search.cost(&'D'), None);
+    }
+
+    #[test]
+    fn astar_reopens_nodes_reached_more_cheaply() {
+        // Admissible but inconsistent heuristic, C is first reached through A
+        let graph = [
+            ('S', 'A', 1),
+            ('S', 'B', 2),
+            ('A', 'C', 5),
+            ('B', 'C', 1),
+            ('C', 'G', 10),
+        ];
+        let heuristic = |node: &char| if *node == 'B' { 9 } else { 0 };
+        let result = astar('S', edges(&graph), heuristic, |node| *node == 'G');
+
+        assert_eq!(result, Some((vec!['S', 'B', 'C', 'G'], 13)));
+    }
+
+    #[test]
+    fn astar_without_reachable_goal() {
+        let graph = [('S', 'A', 1)];
+        let result = astar('S', edges(&graph), |_| 0, |node| *node == 'G');
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn connected_components_of_undirected_graph() {
+        let neighbours = |&node: &u32| match node {
+            1 => vec![2],
+            2 => vec![1, 3],
+            3 => vec![2],
+            4 => vec![5],
+            5 => vec![4],
+            _ => vec![],
+        };
+        let components = connected_components([1, 2, 3, 4, 5, 6], neighbours);
+
+        assert_eq!(
+            components,
+            vec![
+                HashSet::from([1, 2, 3]),
+                HashSet::from([4, 5]),
+                HashSet::from([6]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use aoc_2023::solution::{self, Part, Runner};
+use aoc_2023::utils::{self, Parts};
+
+use crate::cli::VerifyArgs;
+use crate::table;
+
+/// Expected answers of both parts, `None` if the answer is not known
+type Expected = (Option<String>, Option<String>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Fail,
+    Error,
+    Skip,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail => write!(f, "FAIL"),
+            Status::Error => write!(f, "ERROR"),
+            Status::Skip => write!(f, "SKIP"),
+        }
+    }
+}
+
+struct Row {
+    day: u8,
+    file: String,
+    part: Option<Part>,
+    status: Status,
+    expected: String,
+    actual: String,
+    warnings: Vec<String>,
+}
+
+/// Parse answers file
+///
+/// Each non-empty line not starting with `#` contains input file name and
+/// expected answers of both parts separated by whitespace. `-` marks
+/// a part without known answer (e.g. inputs meant only for part 2).
+fn parse_answers(input: &str) -> Result<HashMap<String, Expected>, Box<dyn std::error::Error>> {
+    let mut answers = HashMap::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [file, part1, part2] = fields[..] else {
+            return Err(format!("Invalid answers line {}: {}", i + 1, line).into());
+        };
+
+        let answer = |answer: &str| match answer {
+            "-" => None,
+            answer => Some(answer.to_owned()),
+        };
+
+        answers.insert(file.to_owned(), (answer(part1), answer(part2)));
+    }
+
+    Ok(answers)
+}
+
+fn verify_file(runner: &dyn Runner, file: &str, expected: Option<&Expected>) -> Vec<Row> {
+    let day = runner.day();
+    let name = Path::new(file)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(file)
+        .to_owned();
+
+    let row = |part, status, expected: &str, actual: &str| Row {
+        day,
+        file: name.clone(),
+        part,
+        status,
+        expected: expected.to_owned(),
+        actual: actual.to_owned(),
+        warnings: Vec::new(),
+    };
+
+    let Some((expected_p1, expected_p2)) = expected else {
+        return vec![row(None, Status::Skip, "", "no expected answers")];
+    };
+
+    let parts = Parts {
+        part1: expected_p1.is_some(),
+        part2: expected_p2.is_some(),
+    };
+
+    let results = match solution::solve_file(runner, file, parts) {
+        Ok(results) => results.parts,
+        Err(e) => return vec![row(None, Status::Error, "", &e.to_string())],
+    };
+
+    results
+        .into_iter()
+        .map(|result| {
+            let expected = match result.part {
+                Part::Part1 => expected_p1,
+                Part::Part2 => expected_p2,
+            };
+            let expected = expected.as_deref().unwrap_or_default();
+
+            let row = match result.answer {
+                Ok(actual) if actual == expected => {
+                    row(Some(result.part), Status::Pass, expected, &actual)
+                }
+                Ok(actual) => row(Some(result.part), Status::Fail, expected, &actual),
+                Err(e) => row(Some(result.part), Status::Error, expected, &e.to_string()),
+            };
+            Row {
+                warnings: result.warnings,
+                ..row
+            }
+        })
+        .collect()
+}
+
+fn print_rows(rows: &[Row]) {
+    let header = ["DAY", "INPUT", "PART", "STATUS", "EXPECTED", "ACTUAL"];
+    let cells = rows
+        .iter()
+        .map(|row| {
+            [
+                format!("{:02}", row.day),
+                row.file.clone(),
+                row.part.map(|part| part.to_string()).unwrap_or_default(),
+                row.status.to_string(),
+                row.expected.clone(),
+                row.actual.clone(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    table::print_table(header, &cells);
+}
+
+/// Run all inputs of selected days and compare results with expected answers
+///
+/// Returns `false` if any answer doesn't match or fails to compute.
+pub fn verify(
+    registry: &[Box<dyn Runner>],
+    input_dir: &Path,
+    args: &VerifyArgs,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let answers_file = match &args.answers {
+        Some(file) => file.clone(),
+        None => input_dir.join("answers.txt"),
+    };
+    let answers = parse_answers(&std::fs::read_to_string(answers_file)?)?;
+
+    let days = match args.days.days() {
+        Some(days) => days,
+        None => registry.iter().map(|runner| runner.day()).collect(),
+    };
+
+    let mut rows = Vec::new();
+
+    for day in days {
+        let runner = registry
+            .iter()
+            .find(|runner| runner.day() == day)
+            .ok_or(format!("Day {} is not implemented", day))?;
+
+        for file in utils::input_files(input_dir, &format!("day{:02}", day))
+            .map_err(|e| format!("Cannot read input directory {}: {}", input_dir.display(), e))?
+        {
+            let name = Path::new(&file).file_name().and_then(|name| name.to_str());
+            let expected = name.and_then(|name| answers.get(name));
+            rows.extend(verify_file(runner.as_ref(), &file, expected));
+        }
+    }
+
+    print_rows(&rows);
+
+    for row in &rows {
+        for warning in &row.warnings {
+            let part = row.part.map(|part| part.to_string()).unwrap_or_default();
+            println!("Warning: {:02} {} {}: {}", row.day, row.file, part, warning);
+        }
+    }
+
+    let count = |status| rows.iter().filter(|row| row.status == status).count();
+    println!();
+    println!(
+        "{} passed, {} failed, {} errors, {} skipped",
+        count(Status::Pass),
+        count(Status::Fail),
+        count(Status::Error),
+        count(Status::Skip)
+    );
+
+    Ok(count(Status::Fail) == 0 && count(Status::Error) == 0)
+}